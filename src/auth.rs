@@ -1,37 +1,195 @@
+use crate::password_policy::PasswordPolicy;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
 use sha2::{Digest, Sha256};
 use std::fs::{File, OpenOptions};
 use std::io::{self, BufRead, BufReader, Write};
 use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 const USERS_FILE: &str = "users.txt";
 
+// Argon2id tuning: ~19 MiB memory, 2 iterations, single-lane parallelism —
+// conservative defaults suitable for an interactive login prompt.
+const ARGON2_MEM_COST_KIB: u32 = 19_456;
+const ARGON2_TIME_COST: u32 = 2;
+const ARGON2_PARALLELISM: u32 = 1;
+
+// Account lockout: after this many consecutive failed logins, the account is
+// locked for an exponentially growing cooldown (1s, 2s, 4s, ...).
+const LOCKOUT_THRESHOLD: u32 = 5;
+const LOCKOUT_BASE_SECS: u64 = 1;
+
+// Password aging defaults applied to newly registered accounts: no minimum
+// age (a password can be changed immediately) and a 90-day maximum age
+// before login starts warning that it should be rotated.
+const DEFAULT_MIN_AGE_SECS: u64 = 0;
+const DEFAULT_MAX_AGE_SECS: u64 = 90 * 24 * 3600;
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Print `prompt` and read a line with terminal echo disabled, so the
+/// password typed isn't visible on screen or left in scrollback.
+fn read_hidden_line(prompt: &str) -> io::Result<String> {
+    rpassword::prompt_password(prompt)
+}
+
+fn argon2() -> Argon2<'static> {
+    let params = Params::new(
+        ARGON2_MEM_COST_KIB,
+        ARGON2_TIME_COST,
+        ARGON2_PARALLELISM,
+        None,
+    )
+    .expect("valid Argon2 params");
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+}
+
+/// A user's privilege level. The first account ever registered bootstraps as
+/// `Admin`; everyone after that starts as a plain `Player`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    Admin,
+    Player,
+}
+
+impl Role {
+    fn as_str(self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Player => "player",
+        }
+    }
+
+    fn from_str(s: &str) -> Self {
+        match s {
+            "admin" => Role::Admin,
+            _ => Role::Player,
+        }
+    }
+}
+
+/// One line of `users.txt`, modeled on the Unix shadow-file layout:
+/// `username:hash:last_changed:min_age:max_age:failed_count:locked_until:role`.
+/// All timestamps/durations are Unix epoch seconds.
 #[derive(Clone, Debug)]
-pub struct User {
+pub struct UserRecord {
     pub username: String,
     password_hash: String,
+    last_changed: u64,
+    min_age: u64,
+    max_age: u64,
+    failed_count: u32,
+    locked_until: u64,
+    pub role: Role,
 }
 
-impl User {
+impl UserRecord {
     pub fn new(username: String, password: String) -> Self {
         Self {
             username,
             password_hash: Self::hash_password(&password),
+            last_changed: now_secs(),
+            min_age: DEFAULT_MIN_AGE_SECS,
+            max_age: DEFAULT_MAX_AGE_SECS,
+            failed_count: 0,
+            locked_until: 0,
+            role: Role::Player,
         }
     }
 
     fn hash_password(password: &str) -> String {
+        let salt = SaltString::generate(&mut OsRng);
+        argon2()
+            .hash_password(password.as_bytes(), &salt)
+            .expect("Argon2 hashing failed")
+            .to_string()
+    }
+
+    /// The legacy scheme stored a bare 64-char hex SHA-256 digest.
+    fn is_legacy_sha256(hash: &str) -> bool {
+        hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit())
+    }
+
+    fn verify_legacy_sha256(password: &str, hash: &str) -> bool {
         let mut hasher = Sha256::new();
         hasher.update(password.as_bytes());
-        format!("{:x}", hasher.finalize())
+        format!("{:x}", hasher.finalize()) == hash
     }
 
     fn verify_password(&self, password: &str) -> bool {
-        self.password_hash == Self::hash_password(password)
+        if Self::is_legacy_sha256(&self.password_hash) {
+            Self::verify_legacy_sha256(password, &self.password_hash)
+        } else {
+            match PasswordHash::new(&self.password_hash) {
+                Ok(parsed) => argon2()
+                    .verify_password(password.as_bytes(), &parsed)
+                    .is_ok(),
+                Err(_) => false,
+            }
+        }
+    }
+
+    /// True if this user's stored hash still uses the legacy SHA-256 scheme
+    /// and should be upgraded to Argon2id on the next successful login.
+    fn needs_upgrade(&self) -> bool {
+        Self::is_legacy_sha256(&self.password_hash)
+    }
+
+    /// True if `max_age` is enabled and the password is older than it.
+    fn password_expired(&self) -> bool {
+        self.max_age > 0 && now_secs().saturating_sub(self.last_changed) > self.max_age
+    }
+
+    fn to_line(&self) -> String {
+        format!(
+            "{}:{}:{}:{}:{}:{}:{}:{}",
+            self.username,
+            self.password_hash,
+            self.last_changed,
+            self.min_age,
+            self.max_age,
+            self.failed_count,
+            self.locked_until,
+            self.role.as_str()
+        )
+    }
+
+    /// Parse a `users.txt` line, defaulting any missing trailing fields so
+    /// records written before a field existed keep loading.
+    fn from_line(line: &str) -> Option<Self> {
+        let parts: Vec<&str> = line.split(':').collect();
+        if parts.len() < 2 {
+            return None;
+        }
+        Some(Self {
+            username: parts[0].to_string(),
+            password_hash: parts[1].to_string(),
+            last_changed: parts.get(2).and_then(|s| s.parse().ok()).unwrap_or(0),
+            min_age: parts
+                .get(3)
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(DEFAULT_MIN_AGE_SECS),
+            max_age: parts
+                .get(4)
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(DEFAULT_MAX_AGE_SECS),
+            failed_count: parts.get(5).and_then(|s| s.parse().ok()).unwrap_or(0),
+            locked_until: parts.get(6).and_then(|s| s.parse().ok()).unwrap_or(0),
+            role: parts.get(7).map(|s| Role::from_str(s)).unwrap_or(Role::Player),
+        })
     }
 }
 
 pub struct AuthSystem {
-    current_user: Option<User>,
+    current_user: Option<UserRecord>,
+    password_policy: PasswordPolicy,
 }
 
 impl AuthSystem {
@@ -39,7 +197,10 @@ impl AuthSystem {
         if !Path::new(USERS_FILE).exists() {
             File::create(USERS_FILE).expect("Failed to create users file");
         }
-        Self { current_user: None }
+        Self {
+            current_user: None,
+            password_policy: PasswordPolicy::default(),
+        }
     }
 
     pub fn register(&mut self) -> io::Result<bool> {
@@ -65,39 +226,33 @@ impl AuthSystem {
             return Ok(false);
         }
 
-        print!("Enter password: ");
-        io::stdout().flush()?;
-        let mut password = String::new();
-        io::stdin().read_line(&mut password)?;
-        let password = password.trim().to_string();
-
-        if password.is_empty() {
-            println!("❌ Password cannot be empty!");
-            return Ok(false);
-        }
+        let password = read_hidden_line("Enter password: ")?;
 
-        if password.len() < 6 {
-            println!("❌ Password must be at least 6 characters!");
+        let problems = self.password_policy.validate(&password, &username);
+        if !problems.is_empty() {
+            println!("❌ Password does not meet the required policy:");
+            for problem in &problems {
+                println!("   - {}", problem);
+            }
             return Ok(false);
         }
 
-        print!("Confirm password: ");
-        io::stdout().flush()?;
-        let mut confirm = String::new();
-        io::stdin().read_line(&mut confirm)?;
-        let confirm = confirm.trim().to_string();
+        let confirm = read_hidden_line("Confirm password: ")?;
 
         if password != confirm {
             println!("❌ Passwords don't match!");
             return Ok(false);
         }
 
-        let user = User::new(username.clone(), password);
+        let mut user = UserRecord::new(username.clone(), password);
+        if self.is_users_file_empty()? {
+            user.role = Role::Admin;
+        }
         let mut file = OpenOptions::new()
             .create(true)
             .append(true)
             .open(USERS_FILE)?;
-        writeln!(file, "{}:{}", user.username, user.password_hash)?;
+        writeln!(file, "{}", user.to_line())?;
 
         println!("✅ Registration successful! You can now login.");
         Ok(true)
@@ -111,18 +266,49 @@ impl AuthSystem {
         io::stdin().read_line(&mut username)?;
         let username = username.trim().to_string();
 
-        print!("Enter password: ");
-        io::stdout().flush()?;
-        let mut password = String::new();
-        io::stdin().read_line(&mut password)?;
-        let password = password.trim().to_string();
+        let password = read_hidden_line("Enter password: ")?;
+
+        if let Some(mut user) = self.load_user(&username)? {
+            let now = now_secs();
+            if user.locked_until > now {
+                println!(
+                    "❌ Account locked due to repeated failed attempts. Try again in {}s.",
+                    user.locked_until - now
+                );
+                return Ok(false);
+            }
 
-        if let Some(user) = self.load_user(&username)? {
             if user.verify_password(&password) {
-                self.current_user = Some(user);
+                if user.needs_upgrade() {
+                    user.password_hash = UserRecord::hash_password(&password);
+                }
+                user.failed_count = 0;
+                user.locked_until = 0;
+                self.rewrite_user(&user)?;
                 println!("✅ Login successful! Welcome, {}!", username);
+                if user.password_expired() {
+                    println!(
+                        "⚠️  Your password is over {} days old — consider changing it.",
+                        user.max_age / 86_400
+                    );
+                }
+                self.current_user = Some(user);
                 return Ok(true);
             }
+
+            user.failed_count += 1;
+            if user.failed_count >= LOCKOUT_THRESHOLD {
+                let extra = (user.failed_count - LOCKOUT_THRESHOLD).min(20);
+                let cooldown = LOCKOUT_BASE_SECS << extra;
+                user.locked_until = now + cooldown;
+                self.rewrite_user(&user)?;
+                println!(
+                    "❌ Too many failed attempts. Account locked for {}s.",
+                    cooldown
+                );
+                return Ok(false);
+            }
+            self.rewrite_user(&user)?;
         }
 
         println!("❌ Invalid username or password!");
@@ -133,7 +319,7 @@ impl AuthSystem {
         self.current_user.is_some()
     }
 
-    pub fn get_current_user(&self) -> Option<&User> {
+    pub fn get_current_user(&self) -> Option<&UserRecord> {
         self.current_user.as_ref()
     }
 
@@ -142,14 +328,137 @@ impl AuthSystem {
         println!("✅ Logged out successfully!");
     }
 
+    /// True if the user file has no account lines yet, used to bootstrap the
+    /// very first registered account as `Admin`.
+    fn is_users_file_empty(&self) -> io::Result<bool> {
+        let file = File::open(USERS_FILE)?;
+        let reader = BufReader::new(file);
+        for line in reader.lines() {
+            if UserRecord::from_line(&line?).is_some() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn require_admin(&self) -> Result<(), String> {
+        match &self.current_user {
+            Some(user) if user.role == Role::Admin => Ok(()),
+            Some(_) => Err("Permission denied: admin role required".to_string()),
+            None => Err("Permission denied: not logged in".to_string()),
+        }
+    }
+
+    /// List every registered username. Admin-only.
+    pub fn list_users(&self) -> Result<Vec<String>, String> {
+        self.require_admin()?;
+        let file = File::open(USERS_FILE).map_err(|e| e.to_string())?;
+        let reader = BufReader::new(file);
+        let mut usernames = Vec::new();
+        for line in reader.lines() {
+            let line = line.map_err(|e| e.to_string())?;
+            if let Some(record) = UserRecord::from_line(&line) {
+                usernames.push(record.username);
+            }
+        }
+        Ok(usernames)
+    }
+
+    /// Remove a user's account entirely. Admin-only.
+    pub fn delete_user(&mut self, username: &str) -> Result<(), String> {
+        self.require_admin()?;
+        let file = File::open(USERS_FILE).map_err(|e| e.to_string())?;
+        let reader = BufReader::new(file);
+
+        let mut lines = Vec::new();
+        let mut found = false;
+        for line in reader.lines() {
+            let line = line.map_err(|e| e.to_string())?;
+            let stored_username = line.split(':').next().unwrap_or("");
+            if stored_username == username {
+                found = true;
+            } else {
+                lines.push(line);
+            }
+        }
+
+        if !found {
+            return Err(format!("User '{}' not found", username));
+        }
+
+        let mut file = File::create(USERS_FILE).map_err(|e| e.to_string())?;
+        for line in lines {
+            writeln!(file, "{}", line).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Change another user's role. Admin-only.
+    pub fn set_role(&mut self, username: &str, role: Role) -> Result<(), String> {
+        self.require_admin()?;
+        let mut user = self
+            .load_user(username)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("User '{}' not found", username))?;
+        user.role = role;
+        self.rewrite_user(&user).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Change `username`'s password. An admin may change a *different*
+    /// account's password without knowing the old one; changing your own
+    /// password always requires the correct current password, admin or not,
+    /// so a hijacked or left-open admin session can't silently take it over.
+    pub fn change_password(
+        &mut self,
+        username: &str,
+        old_password: &str,
+        new_password: &str,
+    ) -> Result<(), String> {
+        let is_self = self
+            .current_user
+            .as_ref()
+            .is_some_and(|u| u.username == username);
+        let is_admin = self
+            .current_user
+            .as_ref()
+            .is_some_and(|u| u.role == Role::Admin);
+        if !is_self && !is_admin {
+            return Err("Permission denied".to_string());
+        }
+
+        let mut user = self
+            .load_user(username)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("User '{}' not found", username))?;
+
+        if is_self && !user.verify_password(old_password) {
+            return Err("Old password is incorrect".to_string());
+        }
+
+        let problems = self.password_policy.validate(new_password, username);
+        if !problems.is_empty() {
+            return Err(problems.join("; "));
+        }
+
+        user.password_hash = UserRecord::hash_password(new_password);
+        user.last_changed = now_secs();
+        self.rewrite_user(&user).map_err(|e| e.to_string())?;
+
+        if is_self {
+            self.current_user = Some(user);
+        }
+        Ok(())
+    }
+
     fn user_exists(&self, username: &str) -> io::Result<bool> {
         let file = File::open(USERS_FILE)?;
         let reader = BufReader::new(file);
 
         for line in reader.lines() {
             let line = line?;
-            if let Some(stored_username) = line.split(':').next() {
-                if stored_username == username {
+            if let Some(record) = UserRecord::from_line(&line) {
+                if record.username == username {
                     return Ok(true);
                 }
             }
@@ -157,21 +466,39 @@ impl AuthSystem {
         Ok(false)
     }
 
-    fn load_user(&self, username: &str) -> io::Result<Option<User>> {
+    /// Rewrite `user`'s stored line in place, used after every login attempt
+    /// and after a transparent legacy-hash upgrade.
+    fn rewrite_user(&self, user: &UserRecord) -> io::Result<()> {
+        let file = File::open(USERS_FILE)?;
+        let reader = BufReader::new(file);
+
+        let mut lines = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            let stored_username = line.split(':').next().unwrap_or("");
+            if stored_username == user.username {
+                lines.push(user.to_line());
+            } else {
+                lines.push(line);
+            }
+        }
+
+        let mut file = File::create(USERS_FILE)?;
+        for line in lines {
+            writeln!(file, "{}", line)?;
+        }
+        Ok(())
+    }
+
+    fn load_user(&self, username: &str) -> io::Result<Option<UserRecord>> {
         let file = File::open(USERS_FILE)?;
         let reader = BufReader::new(file);
 
         for line in reader.lines() {
             let line = line?;
-            let parts: Vec<&str> = line.split(':').collect();
-            if parts.len() == 2 {
-                let stored_username = parts[0];
-                let password_hash = parts[1];
-                if stored_username == username {
-                    return Ok(Some(User {
-                        username: username.to_string(),
-                        password_hash: password_hash.to_string(),
-                    }));
+            if let Some(record) = UserRecord::from_line(&line) {
+                if record.username == username {
+                    return Ok(Some(record));
                 }
             }
         }
@@ -0,0 +1,325 @@
+// Magic-bitboard attack generation: knight/king/pawn attacks are single
+// table lookups, and rook/bishop (and queen, as their union) attacks are
+// looked up via a magic multiplication that maps the relevant occupancy
+// bits straight to a precomputed attack set, instead of ray-walking the
+// board square by square the way the 0x88 move generator does.
+//
+// Squares here are plain 0-63 indices (rank*8 + file), not 0x88 squares;
+// `engine.rs` converts between the two at the boundary.
+
+use crate::engine::{Board, Piece, Sq};
+use crate::zobrist::Zobrist;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::OnceLock;
+
+pub type Bb = u64;
+
+/// Maps a 0x88 square to the plain 0-63 index used by every table in this
+/// module (rank*8 + file; valid 0x88 squares always have file 0-7).
+pub fn bb_index(s: Sq) -> usize {
+    ((s >> 4) * 8) + (s & 7)
+}
+
+const ROOK_DIRS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const BISHOP_DIRS: [(i32, i32); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+const KNIGHT_DIRS: [(i32, i32); 8] = [
+    (2, 1),
+    (2, -1),
+    (-2, 1),
+    (-2, -1),
+    (1, 2),
+    (1, -2),
+    (-1, 2),
+    (-1, -2),
+];
+const KING_DIRS: [(i32, i32); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+fn in_bounds(r: i32, f: i32) -> bool {
+    (0..8).contains(&r) && (0..8).contains(&f)
+}
+
+fn idx(r: i32, f: i32) -> usize {
+    (r * 8 + f) as usize
+}
+
+fn step_attacks(dirs: &[(i32, i32)]) -> [Bb; 64] {
+    let mut out = [0u64; 64];
+    for (sqi, slot) in out.iter_mut().enumerate() {
+        let r = sqi as i32 / 8;
+        let f = sqi as i32 % 8;
+        let mut bb = 0u64;
+        for &(dr, df) in dirs {
+            let (nr, nf) = (r + dr, f + df);
+            if in_bounds(nr, nf) {
+                bb |= 1u64 << idx(nr, nf);
+            }
+        }
+        *slot = bb;
+    }
+    out
+}
+
+/// Bitboard of squares from which a pawn of `white`'s color would attack
+/// each target square (i.e. the reverse of the pawn's own attack pattern).
+fn pawn_attacker_sources(white: bool) -> [Bb; 64] {
+    let mut out = [0u64; 64];
+    for (t, slot) in out.iter_mut().enumerate() {
+        let tr = t as i32 / 8;
+        let tf = t as i32 % 8;
+        let sr = if white { tr - 1 } else { tr + 1 };
+        let mut bb = 0u64;
+        for &sf in &[tf - 1, tf + 1] {
+            if in_bounds(sr, sf) {
+                bb |= 1u64 << idx(sr, sf);
+            }
+        }
+        *slot = bb;
+    }
+    out
+}
+
+/// Relevant-occupancy mask for a slider on `sqi`: every square along each
+/// ray except the last one, since a blocker on the final square can't hide
+/// anything further (there's nothing further).
+fn slider_mask(sqi: usize, dirs: &[(i32, i32)]) -> Bb {
+    let r0 = sqi as i32 / 8;
+    let f0 = sqi as i32 % 8;
+    let mut bb = 0u64;
+    for &(dr, df) in dirs {
+        let (mut r, mut f) = (r0 + dr, f0 + df);
+        while in_bounds(r, f) {
+            if in_bounds(r + dr, f + df) {
+                bb |= 1u64 << idx(r, f);
+            }
+            r += dr;
+            f += df;
+        }
+    }
+    bb
+}
+
+/// Actual attack set for a slider on `sqi` given a concrete set of blockers.
+fn slider_attacks_with_blockers(sqi: usize, dirs: &[(i32, i32)], occ: Bb) -> Bb {
+    let r0 = sqi as i32 / 8;
+    let f0 = sqi as i32 % 8;
+    let mut bb = 0u64;
+    for &(dr, df) in dirs {
+        let (mut r, mut f) = (r0 + dr, f0 + df);
+        while in_bounds(r, f) {
+            let bit = 1u64 << idx(r, f);
+            bb |= bit;
+            if occ & bit != 0 {
+                break;
+            }
+            r += dr;
+            f += df;
+        }
+    }
+    bb
+}
+
+/// One square's magic entry: the relevant-occupancy mask, the magic
+/// multiplier, the shift that maps a masked occupancy to a table index,
+/// and the precomputed attack set for every occupancy subset.
+struct MagicEntry {
+    mask: Bb,
+    magic: u64,
+    shift: u32,
+    table: Vec<Bb>,
+}
+
+impl MagicEntry {
+    fn attacks(&self, occ: Bb) -> Bb {
+        let index = ((occ & self.mask).wrapping_mul(self.magic) >> self.shift) as usize;
+        self.table[index]
+    }
+}
+
+/// Enumerate every subset of `mask`'s set bits (the carry-rippler trick).
+fn subsets_of(mask: Bb) -> Vec<Bb> {
+    let mut subsets = Vec::with_capacity(1 << mask.count_ones());
+    let mut subset = 0u64;
+    loop {
+        subsets.push(subset);
+        subset = subset.wrapping_sub(mask) & mask;
+        if subset == 0 {
+            break;
+        }
+    }
+    subsets
+}
+
+fn find_magic(sqi: usize, dirs: &[(i32, i32)], rng: &mut StdRng) -> MagicEntry {
+    let mask = slider_mask(sqi, dirs);
+    let bits = mask.count_ones();
+    let shift = 64 - bits;
+    let subsets = subsets_of(mask);
+    // The attack set for each occupancy subset never depends on the magic
+    // candidate, so compute it once instead of re-walking every ray on every
+    // attempt - that re-walk was the dominant cost of the search.
+    let attack_sets: Vec<Bb> = subsets
+        .iter()
+        .map(|&occ| slider_attacks_with_blockers(sqi, dirs, occ))
+        .collect();
+
+    loop {
+        let magic: u64 = rng.r#gen::<u64>() & rng.r#gen::<u64>() & rng.r#gen::<u64>();
+        // A good magic spreads `mask`'s bits widely after multiplication;
+        // cheaply reject candidates that don't before paying for the full
+        // collision scan below.
+        if (mask.wrapping_mul(magic) & 0xFF00000000000000).count_ones() < 6 {
+            continue;
+        }
+        let mut table: Vec<Option<Bb>> = vec![None; 1usize << bits];
+        let mut collided = false;
+        for (&occ, &attacks) in subsets.iter().zip(&attack_sets) {
+            let index = ((occ.wrapping_mul(magic)) >> shift) as usize;
+            match table[index] {
+                None => table[index] = Some(attacks),
+                Some(existing) if existing == attacks => {}
+                Some(_) => {
+                    collided = true;
+                    break;
+                }
+            }
+        }
+        if !collided {
+            return MagicEntry {
+                mask,
+                magic,
+                shift,
+                table: table.into_iter().map(|o| o.unwrap_or(0)).collect(),
+            };
+        }
+    }
+}
+
+/// All precomputed attack tables, built once and shared process-wide.
+pub struct Magics {
+    rook: Vec<MagicEntry>,
+    bishop: Vec<MagicEntry>,
+    knight: [Bb; 64],
+    king: [Bb; 64],
+    pawn_attackers: [[Bb; 64]; 2], // [white, black]
+}
+
+impl Magics {
+    fn generate() -> Magics {
+        let mut rng = StdRng::seed_from_u64(0xB17B0A4D_u64);
+        let rook = (0..64).map(|s| find_magic(s, &ROOK_DIRS, &mut rng)).collect();
+        let bishop = (0..64)
+            .map(|s| find_magic(s, &BISHOP_DIRS, &mut rng))
+            .collect();
+        Magics {
+            rook,
+            bishop,
+            knight: step_attacks(&KNIGHT_DIRS),
+            king: step_attacks(&KING_DIRS),
+            pawn_attackers: [pawn_attacker_sources(true), pawn_attacker_sources(false)],
+        }
+    }
+}
+
+static MAGICS: OnceLock<Magics> = OnceLock::new();
+
+fn magics() -> &'static Magics {
+    MAGICS.get_or_init(Magics::generate)
+}
+
+pub fn rook_attacks(sqi: usize, occ: Bb) -> Bb {
+    magics().rook[sqi].attacks(occ)
+}
+
+pub fn bishop_attacks(sqi: usize, occ: Bb) -> Bb {
+    magics().bishop[sqi].attacks(occ)
+}
+
+pub fn queen_attacks(sqi: usize, occ: Bb) -> Bb {
+    rook_attacks(sqi, occ) | bishop_attacks(sqi, occ)
+}
+
+pub fn knight_attacks(sqi: usize) -> Bb {
+    magics().knight[sqi]
+}
+
+pub fn king_attacks(sqi: usize) -> Bb {
+    magics().king[sqi]
+}
+
+/// Squares from which a pawn of the given color would attack `sqi`.
+pub fn pawn_attacker_squares(sqi: usize, white_attacker: bool) -> Bb {
+    magics().pawn_attackers[if white_attacker { 0 } else { 1 }][sqi]
+}
+
+/// Twelve piece bitboards plus per-color and combined occupancy, kept in
+/// sync with `Board::cells` inside `make_move`/`undo_move`/`redo_move` the
+/// same way the cells array itself is updated.
+#[derive(Clone)]
+pub struct Bitboards {
+    pub pieces: [Bb; 12],
+    pub white: Bb,
+    pub black: Bb,
+    pub all: Bb,
+}
+
+impl Bitboards {
+    pub fn empty() -> Self {
+        Bitboards {
+            pieces: [0; 12],
+            white: 0,
+            black: 0,
+            all: 0,
+        }
+    }
+
+    /// Rebuild the bitboards from scratch by scanning `board.cells`.
+    pub fn build(board: &Board) -> Self {
+        let mut bb = Self::empty();
+        for s in 0..128 {
+            if s & 0x88 != 0 {
+                continue;
+            }
+            let p = board.cells[s];
+            if !p.is_empty() {
+                bb.set(s, p);
+            }
+        }
+        bb
+    }
+
+    pub fn set(&mut self, s: Sq, p: Piece) {
+        if let Some(idx) = Zobrist::piece_index(p) {
+            let bit = 1u64 << bb_index(s);
+            self.pieces[idx] |= bit;
+            self.all |= bit;
+            if p.is_white() {
+                self.white |= bit;
+            } else {
+                self.black |= bit;
+            }
+        }
+    }
+
+    pub fn clear(&mut self, s: Sq, p: Piece) {
+        if let Some(idx) = Zobrist::piece_index(p) {
+            let bit = !(1u64 << bb_index(s));
+            self.pieces[idx] &= bit;
+            self.all &= bit;
+            if p.is_white() {
+                self.white &= bit;
+            } else {
+                self.black &= bit;
+            }
+        }
+    }
+}
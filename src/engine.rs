@@ -6,6 +6,7 @@
 // - Simple evaluation (material + piece-square tables)
 // - Terminal UI: ASCII board, play vs engine or engine vs engine, make moves via UCI-like input
 
+use crate::bitboard::{self, Bitboards};
 use crate::transposition::{NodeType, PackedMove, ProbeResult, TranspositionTable};
 use crate::zobrist::Zobrist;
 // use std::cmp::max;
@@ -138,6 +139,9 @@ pub struct Board {
     pub ep: Option<Sq>,   // en passant square
     pub halfmove_clock: u32,
     pub fullmove: u32,
+    pub hash: u64, // Zobrist hash of the current position, maintained incrementally
+    pub bb: Bitboards, // piece bitboards, kept in sync alongside `cells`
+    position_history: Vec<u64>, // hash of every position visited, for repetition detection
     history: Vec<Undo>,
     redo_stack: Vec<Undo>, // ADD THIS: stores undone moves
 }
@@ -152,6 +156,7 @@ struct Undo {
     prev_ep: Option<Sq>,
     prev_halfmove: u32,
     promotion: Option<Piece>, // NEW: Store if there was a promotion
+    prev_hash: u64,           // hash before the move, for O(1) restore on undo
 }
 
 impl Board {
@@ -163,6 +168,9 @@ impl Board {
             ep: None,
             halfmove_clock: 0,
             fullmove: 1,
+            hash: 0,
+            bb: Bitboards::empty(),
+            position_history: Vec::new(),
             history: Vec::new(),
             redo_stack: Vec::new(), // for redo
         }
@@ -224,8 +232,29 @@ impl Board {
         if parts.len() > 5 {
             b.fullmove = parts[5].parse().unwrap_or(1)
         }
+        b.hash = Zobrist::global().lock().unwrap().hash_board(&b);
+        b.position_history.push(b.hash);
+        b.bb = Bitboards::build(&b);
         b
     }
+
+    /// True once the current position should be claimed as a draw: the
+    /// position hash has now occurred three times (counting the present
+    /// one), 100 halfmoves have passed with no pawn move or capture, or
+    /// neither side has enough material left to force checkmate.
+    pub fn is_draw(&self) -> bool {
+        if self.halfmove_clock >= 100 {
+            return true;
+        }
+        if insufficient_material(self) {
+            return true;
+        }
+        self.position_history
+            .iter()
+            .filter(|&&h| h == self.hash)
+            .count()
+            >= 3
+    }
     #[allow(dead_code)]
     fn to_fen(&self) -> String {
         let mut s = String::new();
@@ -328,46 +357,57 @@ impl Board {
         }
         None
     }
-    // Make a move (no validation here) and save undo
-    pub fn make_move(&mut self, from: Sq, to: Sq, promotion: Option<Piece>) {
+    /// Apply a move to the board and return the `Undo` record needed to
+    /// reverse it, without touching the undo/redo history stacks or
+    /// `position_history`. Used by `make_move` and by search, which
+    /// applies and immediately reverses moves far more often than a human
+    /// or UI calls `make_move`/`undo_move`.
+    fn make_move_light(&mut self, from: Sq, to: Sq, promotion: Option<Piece>) -> Undo {
         let captured = self.cells[to];
         let prev_cast = self.castling;
         let prev_ep = self.ep;
         let prev_half = self.halfmove_clock;
         let moved_piece = self.cells[from]; // STORE the moving piece
+        let prev_hash = self.hash;
+        let side_white_before = self.side_white;
 
         // handle special: en passant capture
         let mut _actual_captured = captured;
+        let mut captured_sq = to;
+        let mut is_en_passant = false;
         if let Some(ep_sq) = self.ep {
             // pawn moved to ep square capturing pawn
             if self.cells[from] == Piece::WP && to == ep_sq && (from >> 4) == 4 {
                 // white ep capture
                 let cap_sq = to - 16;
                 _actual_captured = self.cells[cap_sq];
+                captured_sq = cap_sq;
                 self.cells[cap_sq] = Piece::Empty;
+                is_en_passant = true;
             } else if self.cells[from] == Piece::BP && to == ep_sq && (from >> 4) == 3 {
                 // black ep capture
                 let cap_sq = to + 16;
                 _actual_captured = self.cells[cap_sq];
+                captured_sq = cap_sq;
                 self.cells[cap_sq] = Piece::Empty;
+                is_en_passant = true;
             }
         }
+        if !_actual_captured.is_empty() {
+            self.bb.clear(captured_sq, _actual_captured);
+        }
 
         // move piece
         let mut moving = self.cells[from];
         self.cells[from] = Piece::Empty;
+        self.bb.clear(from, moved_piece);
 
         // promotions
         if let Some(prom) = promotion {
             moving = prom;
         }
         self.cells[to] = moving;
-
-        // update castling rights if king or rook moved/captured
-        match from {
-            f if f == sq(0, 4) && self.cells[to] == Piece::BK => {}
-            _ => {}
-        }
+        self.bb.set(to, moving);
 
         // crude castling update
         if moved_piece == Piece::WK {
@@ -392,24 +432,47 @@ impl Board {
         }
 
         // handle castling move proper: move rook
+        let mut is_castle = false;
+        let mut rook_from = 0;
+        let mut rook_to = 0;
         // white castling
         if moved_piece == Piece::WK && from == sq(0, 4) && to == sq(0, 6) {
             // white kingside
-            self.cells[sq(0, 7)] = Piece::Empty;
-            self.cells[sq(0, 5)] = Piece::WR;
+            rook_from = sq(0, 7);
+            rook_to = sq(0, 5);
+            self.cells[rook_from] = Piece::Empty;
+            self.cells[rook_to] = Piece::WR;
+            self.bb.clear(rook_from, Piece::WR);
+            self.bb.set(rook_to, Piece::WR);
+            is_castle = true;
         } else if moved_piece == Piece::WK && from == sq(0, 4) && to == sq(0, 2) {
             // white queenside
-            self.cells[sq(0, 0)] = Piece::Empty;
-            self.cells[sq(0, 3)] = Piece::WR;
+            rook_from = sq(0, 0);
+            rook_to = sq(0, 3);
+            self.cells[rook_from] = Piece::Empty;
+            self.cells[rook_to] = Piece::WR;
+            self.bb.clear(rook_from, Piece::WR);
+            self.bb.set(rook_to, Piece::WR);
+            is_castle = true;
         }
 
         // black castling
         if moved_piece == Piece::BK && from == sq(7, 4) && to == sq(7, 6) {
-            self.cells[sq(7, 7)] = Piece::Empty;
-            self.cells[sq(7, 5)] = Piece::BR;
+            rook_from = sq(7, 7);
+            rook_to = sq(7, 5);
+            self.cells[rook_from] = Piece::Empty;
+            self.cells[rook_to] = Piece::BR;
+            self.bb.clear(rook_from, Piece::BR);
+            self.bb.set(rook_to, Piece::BR);
+            is_castle = true;
         } else if moved_piece == Piece::BK && from == sq(7, 4) && to == sq(7, 2) {
-            self.cells[sq(7, 0)] = Piece::Empty;
-            self.cells[sq(7, 3)] = Piece::BR;
+            rook_from = sq(7, 0);
+            rook_to = sq(7, 3);
+            self.cells[rook_from] = Piece::Empty;
+            self.cells[rook_to] = Piece::BR;
+            self.bb.clear(rook_from, Piece::BR);
+            self.bb.set(rook_to, Piece::BR);
+            is_castle = true;
         }
 
         // update en passant target
@@ -432,21 +495,138 @@ impl Board {
             self.fullmove += 1
         }
 
+        // Fold the move into the hash via the dedicated Zobrist helpers
+        // (each picks exactly one per-move-shape update and already folds
+        // in the side-to-move flip) rather than hand-rolled XORs.
+        let captured_for_hash = if _actual_captured.is_empty() {
+            None
+        } else {
+            Some(_actual_captured)
+        };
+        let zob = Zobrist::global();
+        let mut z = zob.lock().unwrap();
+        let mut h = if is_castle {
+            z.update_castle(prev_hash, from, to, rook_from, rook_to, side_white_before)
+        } else if is_en_passant {
+            z.update_en_passant(prev_hash, from, to, captured_sq, side_white_before)
+        } else if let Some(prom) = promotion {
+            z.update_promotion(prev_hash, from, to, moved_piece, prom, captured_for_hash)
+        } else {
+            z.update_move(prev_hash, from, to, moved_piece, captured_for_hash)
+        };
+        h = z.update_castling(h, prev_cast, self.castling);
+        h = z.update_ep(h, prev_ep, self.ep);
+
         // flip side
         self.side_white = !self.side_white;
+        self.hash = h;
+        debug_assert!(
+            z.verify_hash(self, h),
+            "incremental Zobrist hash diverged from a full recompute"
+        );
+        drop(z);
 
-        // record undo with ALL necessary information
-        self.history.push(Undo {
+        Undo {
             mv_from: from,
             mv_to: to,
             moved_piece, // STORE original piece
             captured: _actual_captured,
             prev_castling: prev_cast,
-            prev_ep: prev_ep,
+            prev_ep,
             prev_halfmove: prev_half,
             promotion, // STORE promotion
-        });
+            prev_hash,
+        }
+    }
+
+    /// Reverse a move previously applied by `make_move_light`, given its
+    /// `Undo` record. Unlike `undo_move`, this doesn't touch the undo/redo
+    /// history stacks or `position_history` — it's for callers (search)
+    /// that apply and immediately reverse a move without wanting it
+    /// remembered.
+    fn unmake_move(&mut self, u: Undo) {
+        // 1. Flip side back
+        self.side_white = !self.side_white;
+
+        // 2. Restore fullmove counter
+        if self.side_white {
+            // If we're back to white's turn, decrement fullmove
+            self.fullmove = self.fullmove.saturating_sub(1).max(1);
+        }
+
+        // 3. Restore the original piece to source square (the pawn, if
+        // this move was a promotion)
+        self.cells[u.mv_from] = u.moved_piece;
+        self.bb.set(u.mv_from, u.moved_piece);
+
+        // 4. Restore captured piece (or empty square)
+        let placed_piece = u.promotion.unwrap_or(u.moved_piece);
+        self.bb.clear(u.mv_to, placed_piece);
+        self.cells[u.mv_to] = u.captured;
+        self.bb.set(u.mv_to, u.captured);
+
+        // 5. Handle en passant capture undo
+        if let Some(ep_sq) = u.prev_ep {
+            if u.moved_piece == Piece::WP && u.mv_to == ep_sq && (u.mv_from >> 4) == 4 {
+                // White en passant - restore black pawn
+                let cap_sq = u.mv_to - 16;
+                self.cells[cap_sq] = Piece::BP;
+                self.bb.set(cap_sq, Piece::BP);
+                self.cells[u.mv_to] = Piece::Empty;
+                self.bb.clear(u.mv_to, u.captured);
+            } else if u.moved_piece == Piece::BP && u.mv_to == ep_sq && (u.mv_from >> 4) == 3 {
+                // Black en passant - restore white pawn
+                let cap_sq = u.mv_to + 16;
+                self.cells[cap_sq] = Piece::WP;
+                self.bb.set(cap_sq, Piece::WP);
+                self.cells[u.mv_to] = Piece::Empty;
+                self.bb.clear(u.mv_to, u.captured);
+            }
+        }
 
+        // 6. Undo castling rook move
+        if u.moved_piece == Piece::WK && u.mv_from == sq(0, 4) {
+            if u.mv_to == sq(0, 6) {
+                // White kingside
+                self.cells[sq(0, 5)] = Piece::Empty;
+                self.cells[sq(0, 7)] = Piece::WR;
+                self.bb.clear(sq(0, 5), Piece::WR);
+                self.bb.set(sq(0, 7), Piece::WR);
+            } else if u.mv_to == sq(0, 2) {
+                // White queenside
+                self.cells[sq(0, 3)] = Piece::Empty;
+                self.cells[sq(0, 0)] = Piece::WR;
+                self.bb.clear(sq(0, 3), Piece::WR);
+                self.bb.set(sq(0, 0), Piece::WR);
+            }
+        } else if u.moved_piece == Piece::BK && u.mv_from == sq(7, 4) {
+            if u.mv_to == sq(7, 6) {
+                // Black kingside
+                self.cells[sq(7, 5)] = Piece::Empty;
+                self.cells[sq(7, 7)] = Piece::BR;
+                self.bb.clear(sq(7, 5), Piece::BR);
+                self.bb.set(sq(7, 7), Piece::BR);
+            } else if u.mv_to == sq(7, 2) {
+                // Black queenside
+                self.cells[sq(7, 3)] = Piece::Empty;
+                self.cells[sq(7, 0)] = Piece::BR;
+                self.bb.clear(sq(7, 3), Piece::BR);
+                self.bb.set(sq(7, 0), Piece::BR);
+            }
+        }
+
+        // 7. Restore previous state
+        self.castling = u.prev_castling;
+        self.ep = u.prev_ep;
+        self.halfmove_clock = u.prev_halfmove;
+        self.hash = u.prev_hash;
+    }
+
+    // Make a move (no validation here) and save undo
+    pub fn make_move(&mut self, from: Sq, to: Sq, promotion: Option<Piece>) {
+        let undo = self.make_move_light(from, to, promotion);
+        self.position_history.push(self.hash);
+        self.history.push(undo);
         // Clear redo stack when new move is made
         self.redo_stack.clear();
     }
@@ -463,76 +643,12 @@ impl Board {
                 prev_ep: self.ep,                   // Current EP
                 prev_halfmove: self.halfmove_clock, // Current halfmove
                 promotion: u.promotion,
+                prev_hash: self.hash, // Current hash
             };
             self.redo_stack.push(redo_entry);
 
-            // Now undo the move
-            // 1. Flip side back
-            self.side_white = !self.side_white;
-
-            // 2. Restore fullmove counter
-            if self.side_white {
-                // If we're back to white's turn, decrement fullmove
-                self.fullmove = self.fullmove.saturating_sub(1).max(1);
-            }
-
-            // 3. Get the piece that's currently on the destination square
-            let _piece_on_to = self.cells[u.mv_to];
-
-            // 4. Restore the original piece to source square
-            // If there was a promotion, restore the original pawn
-            let original_piece = if u.promotion.is_some() {
-                u.moved_piece // This is the pawn before promotion
-            } else {
-                u.moved_piece
-            };
-            self.cells[u.mv_from] = original_piece;
-
-            // 5. Restore captured piece (or empty square)
-            self.cells[u.mv_to] = u.captured;
-
-            // 6. Handle en passant capture undo
-            if let Some(ep_sq) = u.prev_ep {
-                if u.moved_piece == Piece::WP && u.mv_to == ep_sq && (u.mv_from >> 4) == 4 {
-                    // White en passant - restore black pawn
-                    let cap_sq = u.mv_to - 16;
-                    self.cells[cap_sq] = Piece::BP;
-                    self.cells[u.mv_to] = Piece::Empty;
-                } else if u.moved_piece == Piece::BP && u.mv_to == ep_sq && (u.mv_from >> 4) == 3 {
-                    // Black en passant - restore white pawn
-                    let cap_sq = u.mv_to + 16;
-                    self.cells[cap_sq] = Piece::WP;
-                    self.cells[u.mv_to] = Piece::Empty;
-                }
-            }
-
-            // 7. Undo castling rook move
-            if u.moved_piece == Piece::WK && u.mv_from == sq(0, 4) {
-                if u.mv_to == sq(0, 6) {
-                    // White kingside
-                    self.cells[sq(0, 5)] = Piece::Empty;
-                    self.cells[sq(0, 7)] = Piece::WR;
-                } else if u.mv_to == sq(0, 2) {
-                    // White queenside
-                    self.cells[sq(0, 3)] = Piece::Empty;
-                    self.cells[sq(0, 0)] = Piece::WR;
-                }
-            } else if u.moved_piece == Piece::BK && u.mv_from == sq(7, 4) {
-                if u.mv_to == sq(7, 6) {
-                    // Black kingside
-                    self.cells[sq(7, 5)] = Piece::Empty;
-                    self.cells[sq(7, 7)] = Piece::BR;
-                } else if u.mv_to == sq(7, 2) {
-                    // Black queenside
-                    self.cells[sq(7, 3)] = Piece::Empty;
-                    self.cells[sq(7, 0)] = Piece::BR;
-                }
-            }
-
-            // 8. Restore previous state
-            self.castling = u.prev_castling;
-            self.ep = u.prev_ep;
-            self.halfmove_clock = u.prev_halfmove;
+            self.unmake_move(u);
+            self.position_history.pop();
         }
     }
 
@@ -544,6 +660,7 @@ impl Board {
             let prev_cast = self.castling;
             let prev_ep = self.ep;
             let prev_half = self.halfmove_clock;
+            let prev_hash = self.hash;
 
             // Store for future undo
             self.history.push(Undo {
@@ -555,51 +672,88 @@ impl Board {
                 prev_ep: prev_ep,
                 prev_halfmove: prev_half,
                 promotion: u.promotion,
+                prev_hash,
             });
 
+            let side_white_before = self.side_white;
+
             // Handle en passant capture in redo
             let mut _actual_captured = captured;
+            let mut captured_sq = u.mv_to;
+            let mut is_en_passant = false;
             if let Some(ep_sq) = self.ep {
                 if moved_piece == Piece::WP && u.mv_to == ep_sq && (u.mv_from >> 4) == 4 {
                     let cap_sq = u.mv_to - 16;
                     _actual_captured = self.cells[cap_sq];
+                    captured_sq = cap_sq;
                     self.cells[cap_sq] = Piece::Empty;
+                    is_en_passant = true;
                 } else if moved_piece == Piece::BP && u.mv_to == ep_sq && (u.mv_from >> 4) == 3 {
                     let cap_sq = u.mv_to + 16;
                     _actual_captured = self.cells[cap_sq];
+                    captured_sq = cap_sq;
                     self.cells[cap_sq] = Piece::Empty;
+                    is_en_passant = true;
                 }
             }
+            if !_actual_captured.is_empty() {
+                self.bb.clear(captured_sq, _actual_captured);
+            }
 
             // Move the piece
             let mut moving = self.cells[u.mv_from];
             self.cells[u.mv_from] = Piece::Empty;
+            self.bb.clear(u.mv_from, moved_piece);
 
             // Handle promotion
             if let Some(prom) = u.promotion {
                 moving = prom;
             }
             self.cells[u.mv_to] = moving;
+            self.bb.set(u.mv_to, moving);
 
             // Restore castling rights from redo entry
             self.castling = u.prev_castling;
 
             // Handle castling rook move
+            let mut is_castle = false;
+            let mut rook_from = 0;
+            let mut rook_to = 0;
             if moved_piece == Piece::WK && u.mv_from == sq(0, 4) {
                 if u.mv_to == sq(0, 6) {
-                    self.cells[sq(0, 7)] = Piece::Empty;
-                    self.cells[sq(0, 5)] = Piece::WR;
+                    rook_from = sq(0, 7);
+                    rook_to = sq(0, 5);
+                    self.cells[rook_from] = Piece::Empty;
+                    self.cells[rook_to] = Piece::WR;
+                    self.bb.clear(rook_from, Piece::WR);
+                    self.bb.set(rook_to, Piece::WR);
+                    is_castle = true;
                 } else if u.mv_to == sq(0, 2) {
-                    self.cells[sq(0, 0)] = Piece::Empty;
-                    self.cells[sq(0, 3)] = Piece::WR;
+                    rook_from = sq(0, 0);
+                    rook_to = sq(0, 3);
+                    self.cells[rook_from] = Piece::Empty;
+                    self.cells[rook_to] = Piece::WR;
+                    self.bb.clear(rook_from, Piece::WR);
+                    self.bb.set(rook_to, Piece::WR);
+                    is_castle = true;
                 }
             } else if moved_piece == Piece::BK && u.mv_from == sq(7, 4) {
                 if u.mv_to == sq(7, 6) {
-                    self.cells[sq(7, 7)] = Piece::Empty;
-                    self.cells[sq(7, 5)] = Piece::BR;
+                    rook_from = sq(7, 7);
+                    rook_to = sq(7, 5);
+                    self.cells[rook_from] = Piece::Empty;
+                    self.cells[rook_to] = Piece::BR;
+                    self.bb.clear(rook_from, Piece::BR);
+                    self.bb.set(rook_to, Piece::BR);
+                    is_castle = true;
                 } else if u.mv_to == sq(7, 2) {
-                    self.cells[sq(7, 0)] = Piece::Empty;
-                    self.cells[sq(7, 3)] = Piece::BR;
+                    rook_from = sq(7, 0);
+                    rook_to = sq(7, 3);
+                    self.cells[rook_from] = Piece::Empty;
+                    self.cells[rook_to] = Piece::BR;
+                    self.bb.clear(rook_from, Piece::BR);
+                    self.bb.set(rook_to, Piece::BR);
+                    is_castle = true;
                 }
             }
 
@@ -609,8 +763,50 @@ impl Board {
             // Restore halfmove clock from redo entry
             self.halfmove_clock = u.prev_halfmove;
 
+            // Fold the move into the hash via the dedicated Zobrist helpers,
+            // the same way `make_move_light` does.
+            let captured_for_hash = if _actual_captured.is_empty() {
+                None
+            } else {
+                Some(_actual_captured)
+            };
+            let zob = Zobrist::global();
+            let mut z = zob.lock().unwrap();
+            let mut h = if is_castle {
+                z.update_castle(
+                    prev_hash,
+                    u.mv_from,
+                    u.mv_to,
+                    rook_from,
+                    rook_to,
+                    side_white_before,
+                )
+            } else if is_en_passant {
+                z.update_en_passant(prev_hash, u.mv_from, u.mv_to, captured_sq, side_white_before)
+            } else if let Some(prom) = u.promotion {
+                z.update_promotion(
+                    prev_hash,
+                    u.mv_from,
+                    u.mv_to,
+                    moved_piece,
+                    prom,
+                    captured_for_hash,
+                )
+            } else {
+                z.update_move(prev_hash, u.mv_from, u.mv_to, moved_piece, captured_for_hash)
+            };
+            h = z.update_castling(h, prev_cast, self.castling);
+            h = z.update_ep(h, prev_ep, self.ep);
+
             // Flip side
             self.side_white = !self.side_white;
+            self.hash = h;
+            debug_assert!(
+                z.verify_hash(self, h),
+                "incremental Zobrist hash diverged from a full recompute"
+            );
+            drop(z);
+            self.position_history.push(h);
 
             // Update fullmove
             if !self.side_white {
@@ -619,19 +815,25 @@ impl Board {
         }
     }
 
-    // Make a clone and play move, used by search
-    fn make_move_clone(&self, from: Sq, to: Sq, promotion: Option<Piece>) -> Board {
-        // associated functions are those in impl or trait definitions
-        let mut b = self.clone();
-        b.make_move(from, to, promotion);
-        b
+    /// Render `m` as Standard Algebraic Notation. Delegates to the shared PGN
+    /// renderer on a clone, since working out the `+`/`#` suffix requires
+    /// playing the move out to see the resulting position.
+    pub fn move_to_san(&self, m: Move) -> String {
+        crate::pgn::san_for_move(&mut self.clone(), m)
+    }
+
+    /// Resolve a SAN token (e.g. "Nf3", "exd5", "O-O", "e8=Q#") to the legal
+    /// move it refers to in the current position, or `None` if it doesn't
+    /// resolve to exactly one legal move.
+    pub fn san_to_move(&self, s: &str) -> Option<Move> {
+        crate::pgn::parse_san(self, s).ok()
     }
 }
 
 // =====================
 // Move Representation
 // =====================
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Move {
     pub from: Sq,
     pub to: Sq,
@@ -663,9 +865,95 @@ const KING_DELTAS: [i32; 8] = [16, 1, -16, -1, 17, 15, -15, -17];
 const ROOK_DELTAS: [i32; 4] = [16, 1, -16, -1];
 const BISHOP_DELTAS: [i32; 4] = [17, 15, -17, -15];
 
+/// Generate the side-to-move's legal moves directly: compute which enemy
+/// pieces check the king and which own pieces are pinned to it up front,
+/// then filter the pseudo-legal move list against that instead of playing
+/// and unplaying every candidate on a scratch board.
 pub fn gen_moves(board: &Board, moves: &mut Vec<Move>) {
     moves.clear();
     let white = board.side_white;
+
+    let Some(king_sq) = board.find_king(white) else {
+        // No king on the board (hand-built test position) — nothing to
+        // check or pin against, so fall back to the pseudo-legal set.
+        gen_pseudo_legal(board, moves);
+        gen_castling(board, moves);
+        return;
+    };
+
+    let info = compute_check_info(board, king_sq, white);
+
+    let mut pseudo = Vec::new();
+    gen_pseudo_legal(board, &mut pseudo);
+
+    for m in pseudo {
+        if m.from == king_sq {
+            if !is_square_attacked_ignoring(board, m.to, !white, king_sq) {
+                moves.push(m);
+            }
+            continue;
+        }
+
+        if info.checkers.len() >= 2 {
+            // Double check: only the king can move.
+            continue;
+        }
+
+        if info.checkers.len() == 1 {
+            let checker = info.checkers[0];
+            let is_ep_capture_of_checker = board.ep == Some(m.to)
+                && matches!(board.piece_at(m.from), Piece::WP | Piece::BP)
+                && (if white { m.to - 16 } else { m.to + 16 } == checker);
+            if m.to != checker && !info.block_squares.contains(&m.to) && !is_ep_capture_of_checker
+            {
+                continue;
+            }
+        }
+
+        if let Some((_, ray)) = info.pins.iter().find(|(sq, _)| *sq == m.from) {
+            if !ray.contains(&m.to) {
+                continue;
+            }
+        }
+
+        // En passant can expose the king via a horizontal pin through both
+        // the moving and captured pawns even when neither pawn itself is
+        // individually pinned — check the resulting position directly.
+        if board.ep == Some(m.to)
+            && matches!(board.piece_at(m.from), Piece::WP | Piece::BP)
+            && !ep_move_is_legal(board, m.from, m.to, king_sq, white)
+        {
+            continue;
+        }
+
+        moves.push(m);
+    }
+
+    // Can't castle out of, through, or into check. `gen_castling` itself
+    // only checks that the squares between king and rook are empty, so
+    // reject the move here if the king's destination or the square it
+    // crosses along the way is attacked (being in check at all is already
+    // ruled out by `info.checkers.is_empty()`).
+    if info.checkers.is_empty() {
+        let mut castling = Vec::new();
+        gen_castling(board, &mut castling);
+        for m in castling {
+            let crossing_file = if (m.to & 7) == 6 { 5 } else { 3 };
+            let crossing_sq = sq((m.to >> 4) as i32, crossing_file);
+            if !is_square_attacked(board, crossing_sq, !white)
+                && !is_square_attacked(board, m.to, !white)
+            {
+                moves.push(m);
+            }
+        }
+    }
+}
+
+/// All pseudo-legal moves for the side to move: every piece's geometric
+/// moves and captures, without regard to whether they leave the king in
+/// check. `gen_moves` filters this against `compute_check_info`.
+fn gen_pseudo_legal(board: &Board, moves: &mut Vec<Move>) {
+    let white = board.side_white;
     for r in 0..8 {
         for f in 0..8 {
             let s = sq(r, f);
@@ -694,18 +982,6 @@ pub fn gen_moves(board: &Board, moves: &mut Vec<Move>) {
             }
         }
     }
-    // add promotion handling is inside pawn function
-    // castling: naive check
-    gen_castling(board, moves);
-    // filter illegal by checking king in check after move
-    let mut legal = Vec::new();
-    for &m in moves.iter() {
-        let b2 = board.make_move_clone(m.from, m.to, m.promotion);
-        if !is_king_attacked(&b2, !board.side_white) {
-            legal.push(m);
-        }
-    }
-    *moves = legal;
 }
 
 fn gen_leaper_moves(board: &Board, s: Sq, deltas: &[i32], moves: &mut Vec<Move>) {
@@ -847,7 +1123,11 @@ fn gen_pawn_moves(board: &Board, s: Sq, white: bool, moves: &mut Vec<Move>) {
     }
     // en passant
     if let Some(ep) = board.ep {
-        if (ep == (s + 15) || ep == (s + 17) || ep == (s - 15) || ep == (s - 17)) && on_board(ep) {
+        let si = s as i32;
+        let ep_i = ep as i32;
+        if (ep_i == si + 15 || ep_i == si + 17 || ep_i == si - 15 || ep_i == si - 17)
+            && on_board(ep)
+        {
             // ensure correct rank relation
             // playable: pawn diagonally behind ep square
             if white {
@@ -927,120 +1207,506 @@ fn gen_castling(board: &Board, moves: &mut Vec<Move>) {
     }
 }
 
+// =====================
+// Perft (move-generation correctness check)
+// =====================
+/// Count leaf nodes of the legal move tree `depth` plies deep — the standard
+/// engine correctness check. Compare against the known perft values for the
+/// start position and "Kiwipete" to catch bugs in castling, en passant, and
+/// the check-legality filter in `gen_moves`.
+pub fn perft(board: &mut Board, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let mut moves = Vec::new();
+    gen_moves(board, &mut moves);
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    let mut nodes = 0u64;
+    for m in moves {
+        board.make_move(m.from, m.to, m.promotion);
+        nodes += perft(board, depth - 1);
+        board.undo_move();
+    }
+    nodes
+}
+
+/// Like `perft`, but prints each root move in UCI form alongside its subtree
+/// node count, so a divergence against a reference engine's divide output
+/// points straight at the offending move.
+pub fn perft_divide(board: &mut Board, depth: u32) -> u64 {
+    let mut moves = Vec::new();
+    gen_moves(board, &mut moves);
+    let mut total = 0u64;
+    for m in moves {
+        board.make_move(m.from, m.to, m.promotion);
+        let nodes = perft(board, depth.saturating_sub(1));
+        board.undo_move();
+        println!("{}: {}", m.to_string().to_lowercase(), nodes);
+        total += nodes;
+    }
+    println!("Total: {}", total);
+    total
+}
+
 // =====================
 // Attack Detection
 // =====================
 fn is_square_attacked(board: &Board, s: Sq, by_white: bool) -> bool {
-    // pawns
-    if by_white {
-        let attacks = [s as i32 - 17, s as i32 - 15];
-        for &a in attacks.iter() {
-            if a >= 0 && on_board(a as usize) {
-                if board.piece_at(a as usize) == Piece::WP {
-                    return true;
-                }
-            }
-        }
+    is_square_attacked_ignoring_impl(board, s, by_white, None)
+}
+
+/// Like `is_square_attacked`, but treats `ignore` as empty while walking
+/// slider rays. Used to test a king's destination square: the king has
+/// already left `ignore` (its origin), so a slider attacking through it
+/// must still count as attacking the destination.
+fn is_square_attacked_ignoring(board: &Board, s: Sq, by_white: bool, ignore: Sq) -> bool {
+    is_square_attacked_ignoring_impl(board, s, by_white, Some(ignore))
+}
+
+fn is_square_attacked_ignoring_impl(board: &Board, s: Sq, by_white: bool, ignore: Option<Sq>) -> bool {
+    let si = bitboard::bb_index(s);
+    let bb = &board.bb;
+
+    // Knights, pawns, and the king can't themselves occupy `ignore` in a way
+    // that matters here (`ignore` is only ever a square the attacked side's
+    // own king just vacated), so only slider occupancy needs to mask it out.
+    let mut occ = bb.all;
+    if let Some(ig) = ignore {
+        occ &= !(1u64 << bitboard::bb_index(ig));
+    }
+
+    let (pawns, knights, bishops, rooks, queens, king) = if by_white {
+        (
+            bb.pieces[0], bb.pieces[1], bb.pieces[2], bb.pieces[3], bb.pieces[4], bb.pieces[5],
+        )
     } else {
-        let attacks = [s as i32 + 17, s as i32 + 15];
-        for &a in attacks.iter() {
-            if a >= 0 && on_board(a as usize) {
-                if board.piece_at(a as usize) == Piece::BP {
-                    return true;
-                }
+        (
+            bb.pieces[6], bb.pieces[7], bb.pieces[8], bb.pieces[9], bb.pieces[10], bb.pieces[11],
+        )
+    };
+
+    if bitboard::pawn_attacker_squares(si, by_white) & pawns != 0 {
+        return true;
+    }
+    if bitboard::knight_attacks(si) & knights != 0 {
+        return true;
+    }
+    if bitboard::rook_attacks(si, occ) & (rooks | queens) != 0 {
+        return true;
+    }
+    if bitboard::bishop_attacks(si, occ) & (bishops | queens) != 0 {
+        return true;
+    }
+    if bitboard::king_attacks(si) & king != 0 {
+        return true;
+    }
+    false
+}
+
+pub fn is_king_attacked(board: &Board, white_king: bool) -> bool {
+    if let Some(kpos) = board.find_king(white_king) {
+        is_square_attacked(board, kpos, !white_king)
+    } else {
+        true
+    }
+}
+
+/// Check/pin state for the side to move's king, computed once per
+/// `gen_moves` call so pseudo-legal moves can be filtered directly instead
+/// of playing and unplaying each one to test `is_king_attacked`.
+struct CheckInfo {
+    /// Squares of enemy pieces currently attacking the king.
+    checkers: Vec<Sq>,
+    /// Squares that resolve a single sliding check by capturing or
+    /// blocking it (the ray from the king up to and including the
+    /// checker). Empty when there's no check or the checker isn't a
+    /// slider, since a non-sliding check can only be answered by a
+    /// capture of the checker itself or a king move.
+    block_squares: Vec<Sq>,
+    /// Own pieces pinned to the king: the pinned piece's square, and the
+    /// ray of squares (from the king, through the piece, up to and
+    /// including the pinning slider) it's restricted to moving along.
+    pins: Vec<(Sq, Vec<Sq>)>,
+}
+
+fn compute_check_info(board: &Board, king_sq: Sq, white_king: bool) -> CheckInfo {
+    let mut checkers = Vec::new();
+    let mut block_squares = Vec::new();
+
+    // pawns
+    let pawn_deltas: [i32; 2] = if white_king { [15, 17] } else { [-15, -17] };
+    for &d in pawn_deltas.iter() {
+        let a = king_sq as i32 + d;
+        if a >= 0 && on_board(a as usize) {
+            let p = board.piece_at(a as usize);
+            if (white_king && p == Piece::BP) || (!white_king && p == Piece::WP) {
+                checkers.push(a as usize);
             }
         }
     }
     // knights
     for &d in KNIGHT_DELTAS.iter() {
-        let a = s as i32 + d;
+        let a = king_sq as i32 + d;
         if a >= 0 && on_board(a as usize) {
             let p = board.piece_at(a as usize);
-            if (by_white && p == Piece::WN) || (!by_white && p == Piece::BN) {
-                return true;
+            if (white_king && p == Piece::BN) || (!white_king && p == Piece::WN) {
+                checkers.push(a as usize);
             }
         }
     }
-    // sliders
-    for &d in ROOK_DELTAS.iter() {
-        let mut a = s as i32 + d;
+    // sliding checkers (rook/queen on orthogonals, bishop/queen on
+    // diagonals), recording the ray up to the checker for block detection
+    for &d in ROOK_DELTAS.iter().chain(BISHOP_DELTAS.iter()) {
+        let is_rook_ray = ROOK_DELTAS.contains(&d);
+        let mut a = king_sq as i32 + d;
+        let mut ray = Vec::new();
         while a >= 0 && on_board(a as usize) {
-            let p = board.piece_at(a as usize);
+            let au = a as usize;
+            let p = board.piece_at(au);
             if !p.is_empty() {
-                if (by_white && (p == Piece::WR || p == Piece::WQ))
-                    || (!by_white && (p == Piece::BR || p == Piece::BQ))
-                {
-                    return true;
+                let checks = if is_rook_ray {
+                    (white_king && (p == Piece::BR || p == Piece::BQ))
+                        || (!white_king && (p == Piece::WR || p == Piece::WQ))
+                } else {
+                    (white_king && (p == Piece::BB || p == Piece::BQ))
+                        || (!white_king && (p == Piece::WB || p == Piece::WQ))
+                };
+                if checks {
+                    checkers.push(au);
+                    ray.push(au);
+                    block_squares.extend(ray);
                 }
                 break;
             }
+            ray.push(au);
             a += d;
         }
     }
-    for &d in BISHOP_DELTAS.iter() {
-        let mut a = s as i32 + d;
-        while a >= 0 && on_board(a as usize) {
-            let p = board.piece_at(a as usize);
+
+    // pins: walk each of the 8 directions for a lone friendly piece
+    // followed by a matching enemy slider
+    let mut pins = Vec::new();
+    for &d in ROOK_DELTAS.iter().chain(BISHOP_DELTAS.iter()) {
+        let is_rook_ray = ROOK_DELTAS.contains(&d);
+        let mut a = king_sq as i32 + d;
+        let mut ray = Vec::new();
+        let mut friendly: Option<Sq> = None;
+        loop {
+            if a < 0 || !on_board(a as usize) {
+                break;
+            }
+            let au = a as usize;
+            ray.push(au);
+            let p = board.piece_at(au);
             if !p.is_empty() {
-                if (by_white && (p == Piece::WB || p == Piece::WQ))
-                    || (!by_white && (p == Piece::BB || p == Piece::BQ))
-                {
-                    return true;
+                let is_friendly = (white_king && p.is_white()) || (!white_king && p.is_black());
+                if let Some(friendly_sq) = friendly {
+                    if !is_friendly {
+                        let pins_as = if is_rook_ray {
+                            p == Piece::WR || p == Piece::WQ || p == Piece::BR || p == Piece::BQ
+                        } else {
+                            p == Piece::WB || p == Piece::WQ || p == Piece::BB || p == Piece::BQ
+                        };
+                        let matches_color =
+                            (white_king && p.is_black()) || (!white_king && p.is_white());
+                        if pins_as && matches_color {
+                            pins.push((friendly_sq, ray.clone()));
+                        }
+                    }
+                    break;
+                } else if is_friendly {
+                    friendly = Some(au);
+                } else {
+                    break; // enemy piece first on this ray: no pin
                 }
-                break;
             }
             a += d;
         }
     }
-    // king
-    for &d in KING_DELTAS.iter() {
-        let a = s as i32 + d;
-        if a >= 0 && on_board(a as usize) {
-            let p = board.piece_at(a as usize);
-            if (by_white && p == Piece::WK) || (!by_white && p == Piece::BK) {
-                return true;
-            }
-        }
+
+    CheckInfo {
+        checkers,
+        block_squares,
+        pins,
     }
-    false
 }
 
-pub fn is_king_attacked(board: &Board, white_king: bool) -> bool {
-    if let Some(kpos) = board.find_king(white_king) {
-        is_square_attacked(board, kpos, !white_king)
-    } else {
-        true
+/// En passant can expose the king via a horizontal pin through both the
+/// moving and captured pawns even when neither pawn is individually
+/// pinned, so check the resulting position directly rather than folding
+/// this into the general pin scan.
+fn ep_move_is_legal(board: &Board, from: Sq, to: Sq, king_sq: Sq, white_king: bool) -> bool {
+    let cap_sq = if white_king { to - 16 } else { to + 16 };
+    let moving = if white_king { Piece::WP } else { Piece::BP };
+    let captured = if white_king { Piece::BP } else { Piece::WP };
+    let mut tmp = board.clone();
+    tmp.cells[from] = Piece::Empty;
+    tmp.bb.clear(from, moving);
+    tmp.cells[cap_sq] = Piece::Empty;
+    tmp.bb.clear(cap_sq, captured);
+    tmp.cells[to] = moving;
+    tmp.bb.set(to, moving);
+    !is_square_attacked(&tmp, king_sq, !white_king)
+}
+
+// =====================
+// Draw detection
+// =====================
+/// True if the square is a light square (a1 is dark), used to tell a
+/// same-colored-bishops-only ending apart from an opposite-colored one.
+fn square_is_light(s: Sq) -> bool {
+    ((s >> 4) + (s & 7)) % 2 == 1
+}
+
+/// True when neither side has enough material left to force checkmate:
+/// bare kings, king+single minor vs king, or king+bishop vs king+bishop
+/// with both bishops on the same color of square.
+fn insufficient_material(board: &Board) -> bool {
+    let mut white_minors: Vec<(Piece, Sq)> = Vec::new();
+    let mut black_minors: Vec<(Piece, Sq)> = Vec::new();
+    for r in 0..8 {
+        for f in 0..8 {
+            let s = sq(r, f);
+            match board.piece_at(s) {
+                Piece::WP | Piece::BP | Piece::WR | Piece::BR | Piece::WQ | Piece::BQ => {
+                    return false
+                }
+                p @ (Piece::WN | Piece::WB) => white_minors.push((p, s)),
+                p @ (Piece::BN | Piece::BB) => black_minors.push((p, s)),
+                _ => {}
+            }
+        }
+    }
+    match (white_minors.as_slice(), black_minors.as_slice()) {
+        ([], []) | ([_], []) | ([], [_]) => true,
+        ([(Piece::WB, ws)], [(Piece::BB, bs)]) => square_is_light(*ws) == square_is_light(*bs),
+        _ => false,
     }
 }
 
 // =====================
 // Evaluation
 // =====================
-fn eval(board: &Board) -> i32 {
-    // material values
+/// A piece's material value in centipawns, White-positive (used both for
+/// `material_score` and for scoring captures during move ordering).
+fn piece_value(p: Piece) -> i32 {
+    match p {
+        Piece::WP => 100,
+        Piece::WN => 320,
+        Piece::WB => 330,
+        Piece::WR => 500,
+        Piece::WQ => 900,
+        Piece::WK => 20000,
+        Piece::BP => -100,
+        Piece::BN => -320,
+        Piece::BB => -330,
+        Piece::BR => -500,
+        Piece::BQ => -900,
+        Piece::BK => -20000,
+        _ => 0,
+    }
+}
+
+/// Raw material balance, from White's perspective (positive favors White),
+/// independent of whose turn it is to move.
+pub fn material_score(board: &Board) -> i32 {
     let mut score = 0i32;
     for r in 0..8 {
         for f in 0..8 {
-            let s = sq(r, f);
-            let p = board.piece_at(s);
-            score += match p {
-                Piece::WP => 100,
-                Piece::WN => 320,
-                Piece::WB => 330,
-                Piece::WR => 500,
-                Piece::WQ => 900,
-                Piece::WK => 20000,
-                Piece::BP => -100,
-                Piece::BN => -320,
-                Piece::BB => -330,
-                Piece::BR => -500,
-                Piece::BQ => -900,
-                Piece::BK => -20000,
+            score += piece_value(board.piece_at(sq(r, f)));
+        }
+    }
+    score
+}
+
+// Piece-square tables, indexed `rank*8 + file` with rank 0 = rank 1 (a
+// white piece reads its own square directly; a black piece mirrors the
+// rank so the tables read as "from the moving side's own perspective").
+// Middlegame/endgame pairs exist where the ideal placement shifts with the
+// phase (pawns push harder once promotion is closer, the king trades
+// shelter for activity); the other pieces use one set for both phases.
+type Pst = [i32; 64];
+
+#[rustfmt::skip]
+const PAWN_MG: Pst = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+     5,  5,  5,  0,  0,  5,  5,  5,
+     5,  5, 10, 15, 15, 10,  5,  5,
+     5, 10, 15, 25, 25, 15, 10,  5,
+    10, 15, 20, 30, 30, 20, 15, 10,
+    20, 25, 35, 40, 40, 35, 25, 20,
+    50, 55, 60, 65, 65, 60, 55, 50,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const PAWN_EG: Pst = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,
+     5,  5,  5,  5,  5,  5,  5,  5,
+    15, 15, 15, 15, 15, 15, 15, 15,
+    30, 30, 30, 30, 30, 30, 30, 30,
+    55, 55, 55, 55, 55, 55, 55, 55,
+    90, 90, 90, 90, 90, 90, 90, 90,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_PST: Pst = [
+    -50,-40,-30,-30,-30,-30,-40,-50,
+    -40,-20,  0,  0,  0,  0,-20,-40,
+    -30,  0, 10, 15, 15, 10,  0,-30,
+    -30,  5, 15, 20, 20, 15,  5,-30,
+    -30,  0, 15, 20, 20, 15,  0,-30,
+    -30,  5, 10, 15, 15, 10,  5,-30,
+    -40,-20,  0,  5,  5,  0,-20,-40,
+    -50,-40,-30,-30,-30,-30,-40,-50,
+];
+
+#[rustfmt::skip]
+const BISHOP_PST: Pst = [
+    -20,-10,-10,-10,-10,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5, 10, 10,  5,  0,-10,
+    -10,  5,  5, 10, 10,  5,  5,-10,
+    -10,  0, 10, 10, 10, 10,  0,-10,
+    -10, 10, 10, 10, 10, 10, 10,-10,
+    -10,  5,  0,  0,  0,  0,  5,-10,
+    -20,-10,-10,-10,-10,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const ROOK_PST: Pst = [
+     0,  0,  5, 10, 10,  5,  0,  0,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+     5, 10, 10, 10, 10, 10, 10,  5,
+     0,  0,  0,  5,  5,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const QUEEN_PST: Pst = [
+    -20,-10,-10, -5, -5,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5,  5,  5,  5,  0,-10,
+     -5,  0,  5,  5,  5,  5,  0, -5,
+      0,  0,  5,  5,  5,  5,  0, -5,
+    -10,  5,  5,  5,  5,  5,  0,-10,
+    -10,  0,  5,  0,  0,  0,  0,-10,
+    -20,-10,-10, -5, -5,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const KING_MG: Pst = [
+     20, 30, 10,  0,  0, 10, 30, 20,
+     20, 20,  0,  0,  0,  0, 20, 20,
+    -10,-20,-20,-20,-20,-20,-20,-10,
+    -20,-30,-30,-40,-40,-30,-30,-20,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+];
+
+#[rustfmt::skip]
+const KING_EG: Pst = [
+    -50,-30,-30,-30,-30,-30,-30,-50,
+    -30,-30,  0,  0,  0,  0,-30,-30,
+    -30,-10, 20, 30, 30, 20,-10,-30,
+    -30,-10, 30, 40, 40, 30,-10,-30,
+    -30,-10, 30, 40, 40, 30,-10,-30,
+    -30,-10, 20, 30, 30, 20,-10,-30,
+    -30,-20,-10,  0,  0,-10,-20,-30,
+    -50,-40,-30,-20,-20,-30,-40,-50,
+];
+
+/// Piece-square contribution to the material balance (White-positive), for
+/// either the middlegame or endgame table set depending on `mg`.
+fn pst_score(board: &Board, mg: bool) -> i32 {
+    let mut score = 0i32;
+    for r in 0..8 {
+        for f in 0..8 {
+            let p = board.piece_at(sq(r, f));
+            if p.is_empty() {
+                continue;
+            }
+            let white = p.is_white();
+            let idx = if white {
+                (r * 8 + f) as usize
+            } else {
+                ((7 - r) * 8 + f) as usize
+            };
+            let value = match p {
+                Piece::WP | Piece::BP if mg => PAWN_MG[idx],
+                Piece::WP | Piece::BP => PAWN_EG[idx],
+                Piece::WN | Piece::BN => KNIGHT_PST[idx],
+                Piece::WB | Piece::BB => BISHOP_PST[idx],
+                Piece::WR | Piece::BR => ROOK_PST[idx],
+                Piece::WQ | Piece::BQ => QUEEN_PST[idx],
+                Piece::WK | Piece::BK if mg => KING_MG[idx],
+                Piece::WK | Piece::BK => KING_EG[idx],
                 _ => 0,
             };
+            score += if white { value } else { -value };
         }
     }
-    // side to move
+    score
+}
+
+/// Game phase out of 24 (24 = full opening material, 0 = bare kings and
+/// pawns), used to blend the middlegame/endgame piece-square scores.
+fn game_phase(board: &Board) -> i32 {
+    let mut phase = 0;
+    for r in 0..8 {
+        for f in 0..8 {
+            phase += match board.piece_at(sq(r, f)) {
+                Piece::WQ | Piece::BQ => 4,
+                Piece::WR | Piece::BR => 2,
+                Piece::WB | Piece::BB | Piece::WN | Piece::BN => 1,
+                _ => 0,
+            };
+        }
+    }
+    phase.min(24)
+}
+
+/// Centipawns per pseudo-legal move available, White's count minus Black's
+/// (freedom-of-movement bonus). Pseudo-legal rather than fully legal moves
+/// keeps this cheap enough to call from `eval` at every search leaf.
+const MOBILITY_BONUS: i32 = 2;
+
+fn mobility_score(board: &Board) -> i32 {
+    let mut own = Vec::new();
+    gen_pseudo_legal(board, &mut own);
+
+    let mut flipped = board.clone();
+    flipped.side_white = !board.side_white;
+    let mut other = Vec::new();
+    gen_pseudo_legal(&flipped, &mut other);
+
+    let (white_moves, black_moves) = if board.side_white {
+        (own.len(), other.len())
+    } else {
+        (other.len(), own.len())
+    };
+    (white_moves as i32 - black_moves as i32) * MOBILITY_BONUS
+}
+
+/// Static evaluation from the side-to-move's perspective, as used by search:
+/// material plus a tapered piece-square term (blended between middlegame and
+/// endgame tables by remaining non-pawn material) plus a mobility bonus.
+pub fn eval(board: &Board) -> i32 {
+    let material = material_score(board);
+    let phase = game_phase(board);
+    let mg = material + pst_score(board, true);
+    let eg = material + pst_score(board, false);
+    let mut score = (mg * phase + eg * (24 - phase)) / 24;
+    score += mobility_score(board);
     if !board.side_white {
         score = -score
     }
@@ -1051,6 +1717,10 @@ fn eval(board: &Board) -> i32 {
 // Search
 // =====================
 
+/// Plies deep enough for any depth/time budget this engine is run with;
+/// bounds the killer-move table.
+const MAX_PLY: usize = 128;
+
 struct SearchInfo {
     nodes: u64,
     start: Instant,
@@ -1058,6 +1728,81 @@ struct SearchInfo {
     // best_move: Option<Move>,
     pub tt: TranspositionTable,
     pub zob: Zobrist,
+    // Hashes of positions reached so far along the current search line, on
+    // top of `board.position_history`'s real game history - make_move_light
+    // doesn't touch that history, so without this a repetition created
+    // purely inside the search tree would go undetected.
+    search_path: Vec<u64>,
+    // The two most recent quiet moves that caused a beta cutoff at each
+    // ply, tried right after captures since they're likely good again in
+    // sibling nodes at the same ply.
+    killers: Vec<[Option<Move>; 2]>,
+    // [from][to] cutoff history for quiet moves, incremented by depth*depth
+    // on a cutoff; the sort key for quiet moves that aren't killers.
+    history: Vec<Vec<i32>>,
+}
+
+impl SearchInfo {
+    /// Record that `m` (a non-capture) caused a beta cutoff at `ply`: bump
+    /// its history score and, if it isn't already the top killer there,
+    /// push it into the killer slots for that ply.
+    fn record_cutoff(&mut self, m: Move, depth: i32, ply: i32) {
+        self.history[m.from][m.to] += depth * depth;
+        let slot = &mut self.killers[(ply as usize).min(MAX_PLY - 1)];
+        if slot[0] != Some(m) {
+            slot[1] = slot[0];
+            slot[0] = Some(m);
+        }
+    }
+}
+
+/// MVV-LVA score for a capture: the value of the captured piece minus the
+/// value of the capturing piece, so a pawn taking a queen sorts far ahead
+/// of a queen taking a pawn.
+fn mvv_lva_score(board: &Board, m: &Move) -> i32 {
+    piece_value(board.piece_at(m.to)).abs() - piece_value(board.piece_at(m.from)).abs()
+}
+
+/// Order `moves` for alpha-beta: the TT move first, then captures by
+/// MVV-LVA, then this ply's killer moves, then quiet moves by history
+/// score.
+fn order_moves(board: &Board, moves: &mut [Move], tt_move: Option<Move>, info: &SearchInfo, ply: i32) {
+    let killers = &info.killers[(ply as usize).min(MAX_PLY - 1)];
+    moves.sort_by_key(|m| {
+        if Some(*m) == tt_move {
+            return i64::MIN;
+        }
+        let capture = !board.piece_at(m.to).is_empty();
+        if capture {
+            return -1_000_000 - mvv_lva_score(board, m) as i64;
+        }
+        if Some(*m) == killers[0] {
+            return -900_001;
+        }
+        if Some(*m) == killers[1] {
+            return -900_000;
+        }
+        -(info.history[m.from][m.to] as i64)
+    });
+}
+
+/// Like `Board::is_draw`, but also checks `search_path` (the moves made so
+/// far inside the current search, which aren't in `board.position_history`)
+/// for threefold repetition.
+fn search_is_draw(board: &Board, search_path: &[u64]) -> bool {
+    if board.halfmove_clock >= 100 {
+        return true;
+    }
+    if insufficient_material(board) {
+        return true;
+    }
+    board
+        .position_history
+        .iter()
+        .chain(search_path.iter())
+        .filter(|&&h| h == board.hash)
+        .count()
+        >= 3
 }
 
 fn piece_to_promo_id(p: Option<Piece>) -> u8 {
@@ -1096,10 +1841,14 @@ fn negamax(
     }
     info.nodes += 1;
 
+    if search_is_draw(board, &info.search_path) {
+        return 0;
+    }
+
     // TT probe
     let key = info.zob.hash_board(board);
     let mut tt_move: Option<Move> = None;
-    match info.tt.probe(key, depth, alpha, beta) {
+    match info.tt.probe(key, depth, alpha, beta, &mut info.zob, ply) {
         ProbeResult::Usable(score, _best) => {
             return score;
         }
@@ -1131,31 +1880,19 @@ fn negamax(
         }
     }
 
-    // ordering: TT move first, then captures
-    if let Some(tm) = tt_move {
-        if let Some(pos) = moves
-            .iter()
-            .position(|m| m.from == tm.from && m.to == tm.to)
-        {
-            let pv = moves.remove(pos);
-            moves.insert(0, pv);
-        }
-    }
-    moves.sort_by_key(|m| {
-        if board.piece_at(m.to).is_empty() {
-            0
-        } else {
-            1
-        }
-    });
+    order_moves(board, &mut moves, tt_move, info, ply);
 
     let mut best = -999999;
     let mut best_move_here: Option<Move> = None;
 
     for m in moves {
-        board.make_move(m.from, m.to, m.promotion);
+        let is_capture = !board.piece_at(m.to).is_empty();
+        let undo = board.make_move_light(m.from, m.to, m.promotion);
+        info.tt.prefetch(board.hash);
+        info.search_path.push(board.hash);
         let val = -negamax(board, depth - 1, ply + 1, -beta, -a, info);
-        board.undo_move();
+        info.search_path.pop();
+        board.unmake_move(undo);
 
         if val > best {
             best = val;
@@ -1168,6 +1905,9 @@ fn negamax(
             a = val;
         }
         if a >= beta {
+            if !is_capture {
+                info.record_cutoff(m, depth, ply);
+            }
             break;
         }
     }
@@ -1188,7 +1928,7 @@ fn negamax(
         None
     };
 
-    info.tt.store(key, depth, best, node_type, best_packed);
+    info.tt.store(key, depth, best, node_type, best_packed, ply);
 
     best
 }
@@ -1200,6 +1940,11 @@ fn quiescence(board: &mut Board, alpha: i32, beta: i32, info: &mut SearchInfo) -
         }
     }
     info.nodes += 1;
+
+    if search_is_draw(board, &info.search_path) {
+        return 0;
+    }
+
     let stand = eval(board);
     if stand >= beta {
         return beta;
@@ -1208,14 +1953,17 @@ fn quiescence(board: &mut Board, alpha: i32, beta: i32, info: &mut SearchInfo) -
     if stand > a {
         a = stand
     }
-    // generate captures
+    // generate captures, best MVV-LVA first
     let mut moves = Vec::new();
     gen_moves(board, &mut moves);
     moves.retain(|m| !board.piece_at(m.to).is_empty());
+    moves.sort_by_key(|m| -mvv_lva_score(board, m));
     for m in moves {
-        board.make_move(m.from, m.to, m.promotion);
+        let undo = board.make_move_light(m.from, m.to, m.promotion);
+        info.search_path.push(board.hash);
         let score = -quiescence(board, -beta, -a, info);
-        board.undo_move();
+        info.search_path.pop();
+        board.unmake_move(undo);
         if score >= beta {
             return beta;
         }
@@ -1226,7 +1974,58 @@ fn quiescence(board: &mut Board, alpha: i32, beta: i32, info: &mut SearchInfo) -
     a
 }
 
-fn search_root(board: &mut Board, max_depth: i32, time_limit_ms: Option<u64>) -> Option<Move> {
+/// Result of a root search: the chosen move, its centipawn score from the
+/// side-to-move's perspective, total nodes searched, and the principal
+/// variation line read back out of the transposition table.
+pub struct AnalysisResult {
+    pub best_move: Option<Move>,
+    pub score: i32,
+    pub nodes: u64,
+    pub pv: Vec<Move>,
+}
+
+/// Walk the transposition table's stored best moves from `board`'s current
+/// position to collect the principal variation, up to `max_len` plies.
+fn extract_pv(board: &Board, tt: &mut TranspositionTable, zob: &mut Zobrist, max_len: usize) -> Vec<Move> {
+    let mut pv = Vec::new();
+    let mut replay = board.clone();
+    for _ in 0..max_len {
+        let key = zob.hash_board(&replay);
+        let Some((from, to, promo)) = tt.best_move_for(key) else {
+            break;
+        };
+        let mv = Move {
+            from,
+            to,
+            promotion: piece_from_promo_id(promo),
+        };
+        let mut legal = Vec::new();
+        gen_moves(&replay, &mut legal);
+        if !legal.iter().any(|m| m.from == mv.from && m.to == mv.to) {
+            break;
+        }
+        replay.make_move(mv.from, mv.to, mv.promotion);
+        pv.push(mv);
+    }
+    pv
+}
+
+/// Render a principal variation the way UCI `info ... pv ...` lines expect:
+/// space-separated coordinate moves (`Move`'s `Display` form with the
+/// promotion letter lowercased).
+fn format_pv(pv: &[Move]) -> String {
+    pv.iter()
+        .map(|m| m.to_string().to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn search_root(
+    board: &mut Board,
+    max_depth: i32,
+    time_limit_ms: Option<u64>,
+    uci: bool,
+) -> AnalysisResult {
     let mut info = SearchInfo {
         nodes: 0,
         start: Instant::now(),
@@ -1234,20 +2033,43 @@ fn search_root(board: &mut Board, max_depth: i32, time_limit_ms: Option<u64>) ->
         // best_move: None,
         tt: TranspositionTable::new_buckets(1 << 18),
         zob: Zobrist::new(),
+        search_path: Vec::new(),
+        killers: vec![[None, None]; MAX_PLY],
+        history: vec![vec![0; 128]; 128],
     };
 
+    // The position two plies ago (i.e. before our own last move), if any -
+    // a root move leading straight back to it is pointless shuffling, not
+    // a path to anything new, so it's mildly penalized below.
+    let repeat_target = if board.position_history.len() >= 2 {
+        Some(board.position_history[board.position_history.len() - 2])
+    } else {
+        None
+    };
+    const ANTI_REPETITION_PENALTY: i32 = 10;
+
     let mut best_move_overall = None;
+    let mut best_score_overall = 0;
 
     // Generate root moves once
     let mut root_moves = Vec::new();
     gen_moves(board, &mut root_moves);
 
     if root_moves.is_empty() {
-        println!("No legal moves available!");
-        return None;
+        if !uci {
+            println!("No legal moves available!");
+        }
+        return AnalysisResult {
+            best_move: None,
+            score: 0,
+            nodes: info.nodes,
+            pv: Vec::new(),
+        };
     }
 
-    println!("Root has {} legal moves", root_moves.len());
+    if !uci {
+        println!("Root has {} legal moves", root_moves.len());
+    }
 
     for depth in 1..=max_depth {
         info.tt.new_search();
@@ -1257,11 +2079,19 @@ fn search_root(board: &mut Board, max_depth: i32, time_limit_ms: Option<u64>) ->
         let mut best_score = -999999;
         let mut best_move_this_depth = None;
 
+        // Best move from the previous (shallower) depth searches first.
+        order_moves(board, &mut root_moves, best_move_overall, &info, 0);
+
         // Search each root move
         for m in &root_moves {
-            board.make_move(m.from, m.to, m.promotion);
-            let val = -negamax(board, depth - 1, 1, -beta, -alpha, &mut info);
-            board.undo_move();
+            let undo = board.make_move_light(m.from, m.to, m.promotion);
+            info.search_path.push(board.hash);
+            let mut val = -negamax(board, depth - 1, 1, -beta, -alpha, &mut info);
+            info.search_path.pop();
+            if repeat_target == Some(board.hash) {
+                val -= ANTI_REPETITION_PENALTY;
+            }
+            board.unmake_move(undo);
 
             if val > best_score {
                 best_score = val;
@@ -1275,13 +2105,21 @@ fn search_root(board: &mut Board, max_depth: i32, time_limit_ms: Option<u64>) ->
             // Check time limit
             if let Some(limit) = info.time_limit {
                 if info.start.elapsed() >= limit {
-                    println!("Time limit reached at depth {}!", depth);
-                    if best_move_overall.is_some() {
-                        return best_move_overall; // Return last complete depth
-                    } else if best_move_this_depth.is_some() {
-                        return best_move_this_depth; // Return incomplete depth if nothing else
+                    if !uci {
+                        println!("Time limit reached at depth {}!", depth);
                     }
-                    return None;
+                    let (chosen, score) = if best_move_overall.is_some() {
+                        (best_move_overall, best_score_overall) // Return last complete depth
+                    } else {
+                        (best_move_this_depth, best_score) // Return incomplete depth if nothing else
+                    };
+                    let pv = extract_pv(board, &mut info.tt, &mut info.zob, max_depth as usize);
+                    return AnalysisResult {
+                        best_move: chosen,
+                        score,
+                        nodes: info.nodes,
+                        pv,
+                    };
                 }
             }
         }
@@ -1289,15 +2127,55 @@ fn search_root(board: &mut Board, max_depth: i32, time_limit_ms: Option<u64>) ->
         // Update overall best move after completing this depth
         if let Some(m) = best_move_this_depth {
             best_move_overall = Some(m);
-            println!(
-                "depth={} score={} nodes={} move={} {}",
+            best_score_overall = best_score;
+
+            // `extract_pv` walks the TT from the root position onward, but
+            // nothing else ever stores an entry for the root itself (only
+            // the children reached from inside `negamax` get stored) - so
+            // without this the very first lookup always misses and the PV
+            // comes back empty. Store the root's own best move here so the
+            // walk has somewhere to start.
+            let root_key = info.zob.hash_board(board);
+            let promo_id = piece_to_promo_id(m.promotion);
+            info.tt.store(
+                root_key,
                 depth,
                 best_score,
-                info.nodes,
-                m,
-                info.tt.stats()
+                NodeType::Exact,
+                Some((m.from, m.to, promo_id)),
+                0,
             );
-        } else {
+
+            if uci {
+                let pv = extract_pv(board, &mut info.tt, &mut info.zob, depth as usize);
+                let time_ms = info.start.elapsed().as_millis();
+                let nps = if time_ms > 0 {
+                    (info.nodes as f64 / time_ms as f64 * 1000.0) as u64
+                } else {
+                    0
+                };
+                println!(
+                    "info depth {} score cp {} nodes {} nps {} time {} hashfull {} pv {}",
+                    depth,
+                    best_score,
+                    info.nodes,
+                    nps,
+                    time_ms,
+                    info.tt.hashfull(),
+                    format_pv(&pv)
+                );
+                io::stdout().flush().ok();
+            } else {
+                println!(
+                    "depth={} score={} nodes={} move={} {}",
+                    depth,
+                    best_score,
+                    info.nodes,
+                    m,
+                    info.tt.stats()
+                );
+            }
+        } else if !uci {
             println!(
                 "depth={} score={} nodes={} (no move found) {}",
                 depth,
@@ -1308,7 +2186,35 @@ fn search_root(board: &mut Board, max_depth: i32, time_limit_ms: Option<u64>) ->
         }
     }
 
-    best_move_overall
+    let pv = extract_pv(board, &mut info.tt, &mut info.zob, max_depth as usize);
+    AnalysisResult {
+        best_move: best_move_overall,
+        score: best_score_overall,
+        nodes: info.nodes,
+        pv,
+    }
+}
+
+/// If the game just ended at `board`'s current position, the message to
+/// show for it: checkmate, stalemate, or a drawn position under
+/// `Board::is_draw`. `None` means the game continues.
+fn game_over_message(board: &Board) -> Option<String> {
+    let mut moves = Vec::new();
+    gen_moves(board, &mut moves);
+    if moves.is_empty() {
+        return Some(if is_king_attacked(board, board.side_white) {
+            let winner = if board.side_white { "Black" } else { "White" };
+            format!("Checkmate! {} wins.", winner)
+        } else {
+            "Stalemate! The game is a draw.".to_string()
+        });
+    }
+    if board.is_draw() {
+        return Some(
+            "Draw (threefold repetition, fifty-move rule, or insufficient material).".to_string(),
+        );
+    }
+    None
 }
 
 // =====================
@@ -1327,12 +2233,26 @@ fn print_help() {
     println!("  redo                - redo previously undone move");
     println!("  hash                - show Zobrist hash of current position");
     println!("  tt                  - show transposition table info");
+    println!("  perft <n>           - count leaf nodes n plies deep");
+    println!("  divide <n>          - perft, broken down per root move");
     println!("  logout              - return to main menu"); // for logout
     println!("  quit                - exit");
 }
 
 pub fn ai_move(board: &mut Board, depth: i32, time_ms: Option<u64>) -> Option<Move> {
-    search_root(board, depth, time_ms)
+    search_root(board, depth, time_ms, false).best_move
+}
+
+/// Like `ai_move`, but also returns the score, node count, and principal
+/// variation behind the chosen move, for analysis/info displays.
+pub fn analyze(board: &mut Board, depth: i32, time_ms: Option<u64>) -> AnalysisResult {
+    search_root(board, depth, time_ms, false)
+}
+
+/// Like `analyze`, but prints UCI `info depth ... score cp ... pv ...` lines
+/// as iterative deepening completes each depth, for the `uci` module's `go`.
+pub fn analyze_uci(board: &mut Board, depth: i32, time_ms: Option<u64>) -> AnalysisResult {
+    search_root(board, depth, time_ms, true)
 }
 
 pub fn run() {
@@ -1458,6 +2378,10 @@ pub fn run() {
                             };
                             board.make_move(from, to, promotion);
                             board.print_board();
+                            if let Some(msg) = game_over_message(&board) {
+                                println!("{}", msg);
+                                break;
+                            }
                         } else {
                             println!("bad squares")
                         }
@@ -1467,6 +2391,10 @@ pub fn run() {
                             println!("engine -> {}", mv);
                             board.make_move(mv.from, mv.to, mv.promotion);
                             board.print_board();
+                            if let Some(msg) = game_over_message(&board) {
+                                println!("{}", msg);
+                                break;
+                            }
                         } else {
                             println!("engine has no move");
                             break;
@@ -1483,6 +2411,30 @@ pub fn run() {
                 println!("TT stats: use 'go depth N' first to see per-depth stats");
                 println!("(TT is created per search in current implementation)");
             }
+            "perft" => {
+                if parts.len() < 2 {
+                    println!("usage: perft <n>");
+                    continue;
+                }
+                if let Ok(d) = parts[1].parse::<u32>() {
+                    let now = Instant::now();
+                    let nodes = perft(&mut board, d);
+                    println!("perft({}) = {} nodes ({:?})", d, nodes, now.elapsed());
+                } else {
+                    println!("usage: perft <n>");
+                }
+            }
+            "divide" => {
+                if parts.len() < 2 {
+                    println!("usage: divide <n>");
+                    continue;
+                }
+                if let Ok(d) = parts[1].parse::<u32>() {
+                    perft_divide(&mut board, d);
+                } else {
+                    println!("usage: divide <n>");
+                }
+            }
             "logout" => {
                 println!("Returning to main menu...");
                 break; // Exit the engine loop, return to main menu
@@ -1497,3 +2449,35 @@ pub fn run() {
 
 // WIP: For v0.2.0 (still testing, it's under serious development)
 // Default branch changing from testing ==> main
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Regression test for the UCI `info ... pv ...` line: `analyze_uci` must
+    /// come back with a non-empty principal variation, not just a bare best
+    /// move, since `extract_pv` walks the TT starting from the root.
+    #[test]
+    fn analyze_uci_returns_a_non_empty_pv() {
+        let mut board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let result = analyze_uci(&mut board, 2, None);
+        assert!(result.best_move.is_some());
+        assert!(!result.pv.is_empty());
+        assert_eq!(result.pv[0], result.best_move.unwrap());
+    }
+
+    /// The standard perft correctness benchmark: leaf-node counts from the
+    /// startpos at depths 1-4 are well-known (20/400/8902/197281) and catch
+    /// move-generation bugs (en passant, castling, promotions) that a
+    /// handful of hand-picked positions would miss.
+    #[test]
+    fn perft_matches_known_startpos_counts() {
+        let mut board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(perft(&mut board, 1), 20);
+        assert_eq!(perft(&mut board, 2), 400);
+        assert_eq!(perft(&mut board, 3), 8902);
+        assert_eq!(perft(&mut board, 4), 197281);
+    }
+}
@@ -0,0 +1,11 @@
+pub mod auth;
+pub mod bitboard;
+pub mod engine;
+pub mod password_policy;
+pub mod pgn;
+pub mod polyglot;
+pub mod tactics;
+pub mod transposition;
+pub mod uci;
+pub mod ui;
+pub mod zobrist;
@@ -3,6 +3,11 @@ use rust_chess_engine::ui::GameController;
 use std::io;
 
 fn main() {
+    if std::env::args().any(|a| a == "--uci") {
+        rust_chess_engine::uci::run();
+        return;
+    }
+
     let mut auth = AuthSystem::new();
 
     // Main menu loop
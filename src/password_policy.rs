@@ -0,0 +1,68 @@
+//! Configurable password-strength rules, checked at registration (and by any
+//! future password-change flow) so a weak password is rejected with every
+//! problem reported at once instead of one check at a time.
+
+/// Minimum/maximum length and required character-class thresholds for a
+/// password. Construct a custom `PasswordPolicy` to tighten or relax these
+/// for a given deployment; `PasswordPolicy::default()` matches the engine's
+/// built-in defaults.
+#[derive(Clone, Debug)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_upper: bool,
+    pub require_lower: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 6,
+            max_length: 128,
+            require_upper: false,
+            require_lower: false,
+            require_digit: false,
+            require_symbol: false,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Check `password` (being set for `username`) against this policy,
+    /// returning every failed rule rather than stopping at the first.
+    pub fn validate(&self, password: &str, username: &str) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if password.len() < self.min_length {
+            problems.push(format!(
+                "Password must be at least {} characters",
+                self.min_length
+            ));
+        }
+        if password.len() > self.max_length {
+            problems.push(format!(
+                "Password must be at most {} characters",
+                self.max_length
+            ));
+        }
+        if self.require_upper && !password.chars().any(|c| c.is_ascii_uppercase()) {
+            problems.push("Password must contain an uppercase letter".to_string());
+        }
+        if self.require_lower && !password.chars().any(|c| c.is_ascii_lowercase()) {
+            problems.push("Password must contain a lowercase letter".to_string());
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            problems.push("Password must contain a digit".to_string());
+        }
+        if self.require_symbol && !password.chars().any(|c| !c.is_ascii_alphanumeric()) {
+            problems.push("Password must contain a symbol".to_string());
+        }
+        if !username.is_empty() && password.eq_ignore_ascii_case(username) {
+            problems.push("Password must not be the same as the username".to_string());
+        }
+
+        problems
+    }
+}
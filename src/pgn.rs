@@ -0,0 +1,409 @@
+// PGN (Portable Game Notation) support: SAN rendering for moves and
+// Seven Tag Roster export. Import/replay lives alongside this in the
+// same module (see `parse_san` and friends) so export/import stay in sync.
+
+use crate::engine::{Board, Move, Piece, Sq, gen_moves, is_king_attacked};
+use crate::ui::GameResult;
+
+fn sq_to_alg(s: Sq) -> String {
+    let r = (s >> 4) as i32;
+    let f = (s & 15) as i32;
+    if r < 0 || r > 7 || f < 0 || f > 7 {
+        return String::from("??");
+    }
+    let file = (b'a' + f as u8) as char;
+    let rank = (1 + r).to_string();
+    format!("{}{}", file, rank)
+}
+
+fn alg_to_sq(s: &str) -> Option<Sq> {
+    let s = s.trim();
+    if s.len() < 2 {
+        return None;
+    }
+    let bytes = s.as_bytes();
+    let f = (bytes[0] as char).to_ascii_lowercase();
+    let rch = bytes[1] as char;
+    if !('a'..='h').contains(&f) || !('1'..='8').contains(&rch) {
+        return None;
+    }
+    let file = (f as u8 - b'a') as usize;
+    let rank = (rch as u8 - b'1') as usize;
+    Some((rank << 4) | file)
+}
+
+fn sq_at(rank: usize, file: usize) -> Sq {
+    (rank << 4) | file
+}
+
+fn piece_for_letter(c: char, white: bool) -> Option<Piece> {
+    match c.to_ascii_uppercase() {
+        'N' => Some(if white { Piece::WN } else { Piece::BN }),
+        'B' => Some(if white { Piece::WB } else { Piece::BB }),
+        'R' => Some(if white { Piece::WR } else { Piece::BR }),
+        'Q' => Some(if white { Piece::WQ } else { Piece::BQ }),
+        'K' => Some(if white { Piece::WK } else { Piece::BK }),
+        _ => None,
+    }
+}
+
+fn piece_letter(p: Piece) -> &'static str {
+    match p {
+        Piece::WN | Piece::BN => "N",
+        Piece::WB | Piece::BB => "B",
+        Piece::WR | Piece::BR => "R",
+        Piece::WQ | Piece::BQ => "Q",
+        Piece::WK | Piece::BK => "K",
+        _ => "",
+    }
+}
+
+/// Render `mv` as SAN and play it on `board` (the board is advanced by the
+/// move as a side effect, since check/mate suffixes require the post-move
+/// position). Callers doing a full-game replay can call this once per move
+/// in order, starting from the initial position.
+pub fn san_for_move(board: &mut Board, mv: Move) -> String {
+    let moving = board.cells[mv.from];
+    let is_pawn = moving == Piece::WP || moving == Piece::BP;
+    let is_king = moving == Piece::WK || moving == Piece::BK;
+
+    if is_king {
+        let from_file = (mv.from & 15) as i32;
+        let to_file = (mv.to & 15) as i32;
+        if (to_file - from_file).abs() == 2 {
+            let mut s = if to_file > from_file {
+                "O-O".to_string()
+            } else {
+                "O-O-O".to_string()
+            };
+            board.make_move(mv.from, mv.to, mv.promotion);
+            append_check_suffix(board, &mut s);
+            return s;
+        }
+    }
+
+    let target_occupied = !board.cells[mv.to].is_empty();
+    let is_ep_capture = is_pawn && !target_occupied && Some(mv.to) == board.ep;
+    let is_capture = target_occupied || is_ep_capture;
+
+    let mut legal_moves = Vec::new();
+    gen_moves(board, &mut legal_moves);
+    let same_dest: Vec<Move> = legal_moves
+        .iter()
+        .copied()
+        .filter(|m| m.to == mv.to && m.from != mv.from && board.cells[m.from] == moving)
+        .collect();
+
+    let mut disambiguation = String::new();
+    if !is_pawn && !same_dest.is_empty() {
+        let from_file = mv.from & 15;
+        let from_rank = mv.from >> 4;
+        let file_unique = same_dest.iter().all(|m| (m.from & 15) != from_file);
+        let rank_unique = same_dest.iter().all(|m| (m.from >> 4) != from_rank);
+        if file_unique {
+            disambiguation.push((b'a' + from_file as u8) as char);
+        } else if rank_unique {
+            disambiguation.push((b'1' + from_rank as u8) as char);
+        } else {
+            disambiguation.push((b'a' + from_file as u8) as char);
+            disambiguation.push((b'1' + from_rank as u8) as char);
+        }
+    }
+
+    let mut s = String::new();
+    if is_pawn {
+        if is_capture {
+            s.push((b'a' + (mv.from & 15) as u8) as char);
+        }
+    } else {
+        s.push_str(piece_letter(moving));
+        s.push_str(&disambiguation);
+    }
+    if is_capture {
+        s.push('x');
+    }
+    s.push_str(&sq_to_alg(mv.to));
+    if let Some(promo) = mv.promotion {
+        s.push('=');
+        s.push_str(piece_letter(promo));
+    }
+
+    board.make_move(mv.from, mv.to, mv.promotion);
+    append_check_suffix(board, &mut s);
+    s
+}
+
+fn append_check_suffix(board: &mut Board, s: &mut String) {
+    if is_king_attacked(board, board.side_white) {
+        let mut moves = Vec::new();
+        gen_moves(board, &mut moves);
+        if moves.is_empty() {
+            s.push('#');
+        } else {
+            s.push('+');
+        }
+    }
+}
+
+/// Maps the controller's `GameResult` to the PGN result token. A game still
+/// in progress (or one that ended by resignation without a recorded winner)
+/// uses the "unknown result" token.
+pub fn result_token(result: Option<GameResult>) -> &'static str {
+    match result {
+        Some(GameResult::WhiteWins) => "1-0",
+        Some(GameResult::BlackWins) => "0-1",
+        Some(GameResult::Draw) | Some(GameResult::Stalemate) => "1/2-1/2",
+        Some(GameResult::Resignation) | None => "*",
+    }
+}
+
+/// Inverse of `result_token`: map a PGN result token back to a `GameResult`.
+/// `Resignation` can't be distinguished from a generic win on replay alone,
+/// so a decisive result is reported as a win rather than a resignation.
+pub fn result_from_token(token: &str) -> Option<GameResult> {
+    match token {
+        "1-0" => Some(GameResult::WhiteWins),
+        "0-1" => Some(GameResult::BlackWins),
+        "1/2-1/2" => Some(GameResult::Draw),
+        _ => None,
+    }
+}
+
+/// Days-since-epoch -> (year, month, day), Howard Hinnant's civil_from_days.
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = (z - era * 146097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = if m <= 2 { y + 1 } else { y };
+    (y, m, d)
+}
+
+fn today_date_tag() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let days = (secs / 86400) as i64;
+    let (y, m, d) = civil_from_days(days);
+    format!("{:04}.{:02}.{:02}", y, m, d)
+}
+
+/// Build a spec-compliant PGN document: Seven Tag Roster followed by the
+/// movetext in numbered pairs, terminated with the result token.
+pub fn format_pgn(white: &str, black: &str, round: &str, result: &str, sans: &[String]) -> String {
+    let mut out = String::new();
+    out.push_str("[Event \"Casual Game\"]\n");
+    out.push_str("[Site \"Rust Chess Engine\"]\n");
+    out.push_str(&format!("[Date \"{}\"]\n", today_date_tag()));
+    out.push_str(&format!("[Round \"{}\"]\n", round));
+    out.push_str(&format!("[White \"{}\"]\n", white));
+    out.push_str(&format!("[Black \"{}\"]\n", black));
+    out.push_str(&format!("[Result \"{}\"]\n", result));
+    out.push('\n');
+
+    let mut movetext = String::new();
+    for (i, pair) in sans.chunks(2).enumerate() {
+        movetext.push_str(&format!("{}. {}", i + 1, pair[0]));
+        if let Some(black_move) = pair.get(1) {
+            movetext.push(' ');
+            movetext.push_str(black_move);
+        }
+        movetext.push(' ');
+    }
+    movetext.push_str(result);
+    out.push_str(&movetext);
+    out.push('\n');
+    out
+}
+
+// =====================
+// PGN Import
+// =====================
+
+/// Parse the `[Tag "Value"]` header lines of a PGN document into key/value pairs.
+pub fn parse_tags(text: &str) -> Vec<(String, String)> {
+    let mut tags = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if !line.starts_with('[') || !line.ends_with(']') {
+            continue;
+        }
+        let inner = &line[1..line.len() - 1];
+        if let Some(sp) = inner.find(' ') {
+            let key = inner[..sp].to_string();
+            let value = inner[sp + 1..].trim().trim_matches('"').to_string();
+            tags.push((key, value));
+        }
+    }
+    tags
+}
+
+/// Strip a leading move-number marker like "12." or "12..." from a movetext token.
+fn strip_move_number(tok: &str) -> &str {
+    let bytes = tok.as_bytes();
+    let mut idx = 0;
+    while idx < bytes.len() && bytes[idx].is_ascii_digit() {
+        idx += 1;
+    }
+    if idx == 0 {
+        return tok;
+    }
+    let mut end = idx;
+    while end < bytes.len() && bytes[end] == b'.' {
+        end += 1;
+    }
+    if end > idx { &tok[end..] } else { tok }
+}
+
+/// Extract the SAN move tokens and, if present, the trailing result token from
+/// a PGN movetext section. Handles move numbers, `{...}` comments, `(...)`
+/// variations (including nested ones) and `$n` NAGs.
+pub fn parse_movetext(text: &str) -> (Vec<String>, Option<String>) {
+    let mut no_comments = String::new();
+    let mut brace_depth = 0u32;
+    for c in text.chars() {
+        match c {
+            '{' => brace_depth += 1,
+            '}' => brace_depth = brace_depth.saturating_sub(1),
+            _ if brace_depth > 0 => {}
+            _ => no_comments.push(c),
+        }
+    }
+
+    let mut no_variations = String::new();
+    let mut paren_depth = 0u32;
+    for c in no_comments.chars() {
+        match c {
+            '(' => paren_depth += 1,
+            ')' => paren_depth = paren_depth.saturating_sub(1),
+            _ if paren_depth > 0 => {}
+            _ => no_variations.push(c),
+        }
+    }
+
+    let mut sans = Vec::new();
+    let mut result = None;
+    for raw in no_variations.split_whitespace() {
+        if raw.starts_with('[') {
+            // Leftover tag-pair line fragment; tags are parsed separately.
+            continue;
+        }
+        if raw.starts_with('$') {
+            continue;
+        }
+        if matches!(raw, "1-0" | "0-1" | "1/2-1/2" | "*") {
+            result = Some(raw.to_string());
+            continue;
+        }
+        let mv = strip_move_number(raw);
+        if !mv.is_empty() {
+            sans.push(mv.to_string());
+        }
+    }
+    (sans, result)
+}
+
+/// Resolve a castling SAN token ("O-O"/"O-O-O") to the matching legal move.
+fn resolve_castling(board: &Board, from: Sq, to: Sq) -> Result<Move, String> {
+    let mut moves = Vec::new();
+    gen_moves(board, &mut moves);
+    moves
+        .into_iter()
+        .find(|m| m.from == from && m.to == to)
+        .ok_or_else(|| "illegal castling move".to_string())
+}
+
+/// Resolve a SAN token to a concrete legal `Move` against `board`, using piece
+/// type, destination, disambiguation, and promotion to pick among legal moves.
+/// Errors if the token doesn't resolve to exactly one legal move.
+pub fn parse_san(board: &Board, token: &str) -> Result<Move, String> {
+    let core = token.trim().trim_end_matches(['+', '#', '!', '?']);
+    if core.is_empty() {
+        return Err("empty move token".to_string());
+    }
+
+    let white = board.side_white;
+
+    if core == "O-O" || core == "0-0" {
+        let (from, to) = if white {
+            (sq_at(0, 4), sq_at(0, 6))
+        } else {
+            (sq_at(7, 4), sq_at(7, 6))
+        };
+        return resolve_castling(board, from, to);
+    }
+    if core == "O-O-O" || core == "0-0-0" {
+        let (from, to) = if white {
+            (sq_at(0, 4), sq_at(0, 2))
+        } else {
+            (sq_at(7, 4), sq_at(7, 2))
+        };
+        return resolve_castling(board, from, to);
+    }
+
+    let mut rest = core;
+    let promotion = if let Some(idx) = rest.find('=') {
+        let promo_char = rest[idx + 1..]
+            .chars()
+            .next()
+            .ok_or_else(|| format!("malformed promotion in '{}'", token))?;
+        rest = &rest[..idx];
+        Some(
+            piece_for_letter(promo_char, white)
+                .ok_or_else(|| format!("unknown promotion piece '{}' in '{}'", promo_char, token))?,
+        )
+    } else {
+        None
+    };
+
+    let mut chars = rest.chars();
+    let (piece_type, body) = match chars.next() {
+        Some(c) if matches!(c, 'N' | 'B' | 'R' | 'Q' | 'K') => {
+            (piece_for_letter(c, white).unwrap(), chars.as_str())
+        }
+        _ => (if white { Piece::WP } else { Piece::BP }, rest),
+    };
+    let body: String = body.chars().filter(|&c| c != 'x').collect();
+    if body.len() < 2 {
+        return Err(format!("malformed move '{}'", token));
+    }
+
+    let dest = &body[body.len() - 2..];
+    let to = alg_to_sq(dest).ok_or_else(|| format!("bad destination square in '{}'", token))?;
+
+    let disambiguation = &body[..body.len() - 2];
+    let disambig_file = disambiguation
+        .chars()
+        .find(|c| ('a'..='h').contains(c))
+        .map(|c| (c as u8 - b'a') as usize);
+    let disambig_rank = disambiguation
+        .chars()
+        .find(|c| ('1'..='8').contains(c))
+        .map(|c| (c as u8 - b'1') as usize);
+
+    let mut moves = Vec::new();
+    gen_moves(board, &mut moves);
+    let candidates: Vec<Move> = moves
+        .into_iter()
+        .filter(|m| {
+            board.cells[m.from] == piece_type
+                && m.to == to
+                && m.promotion == promotion
+                && disambig_file.is_none_or(|f| (m.from & 15) == f)
+                && disambig_rank.is_none_or(|r| (m.from >> 4) == r)
+        })
+        .collect();
+
+    match candidates.len() {
+        0 => Err(format!("illegal move '{}'", token)),
+        1 => Ok(candidates[0]),
+        _ => Err(format!("ambiguous move '{}'", token)),
+    }
+}
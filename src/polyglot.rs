@@ -0,0 +1,117 @@
+// Reader for PolyGlot opening-book `.bin` files: a sequence of 16-byte,
+// big-endian entries sorted by key, as documented at
+// https://hgm.nubati.net/book_format.html. Multiple entries can share a key
+// (one per candidate move for that position); `moves` decodes all of them
+// against the position's Zobrist hash from `zobrist::polyglot_hash`.
+
+use crate::engine::{Board, Move, Piece, Sq};
+use crate::zobrist::polyglot_hash;
+use std::fs::File;
+use std::io::{self, Read};
+
+/// One raw 16-byte book entry. `learn` carries engine-specific learning data
+/// that this reader doesn't interpret.
+#[derive(Clone, Copy, Debug)]
+pub struct BookEntry {
+    pub key: u64,
+    pub raw_move: u16,
+    pub weight: u16,
+    pub learn: u32,
+}
+
+impl BookEntry {
+    /// Decode the packed move against `board`, expanding PolyGlot's
+    /// "king captures rook" castling encoding into the to-square the
+    /// engine's own move generator produces (`gen_castling` moves the king
+    /// two files towards the rook rather than onto it).
+    pub fn decode_move(&self, board: &Board) -> Move {
+        let to_file = (self.raw_move & 0x7) as usize;
+        let to_rank = ((self.raw_move >> 3) & 0x7) as usize;
+        let from_file = ((self.raw_move >> 6) & 0x7) as usize;
+        let from_rank = ((self.raw_move >> 9) & 0x7) as usize;
+        let promo_bits = (self.raw_move >> 12) & 0x7;
+
+        let from: Sq = (from_rank << 4) | from_file;
+        let mut to: Sq = (to_rank << 4) | to_file;
+
+        let is_king = matches!(board.cells[from], Piece::WK | Piece::BK);
+        if is_king && from_file == 4 && to_file == 7 {
+            to = (to_rank << 4) | 6; // O-O: e.g. e1h1 -> e1g1
+        } else if is_king && from_file == 4 && to_file == 0 {
+            to = (to_rank << 4) | 2; // O-O-O: e.g. e1a1 -> e1c1
+        }
+
+        let promotion = match promo_bits {
+            1 => Some(if board.side_white { Piece::WN } else { Piece::BN }),
+            2 => Some(if board.side_white { Piece::WB } else { Piece::BB }),
+            3 => Some(if board.side_white { Piece::WR } else { Piece::BR }),
+            4 => Some(if board.side_white { Piece::WQ } else { Piece::BQ }),
+            _ => None,
+        };
+
+        Move {
+            from,
+            to,
+            promotion,
+        }
+    }
+}
+
+/// A loaded PolyGlot book, kept in the file's own key-sorted order so
+/// lookups are a binary search.
+pub struct PolyglotBook {
+    entries: Vec<BookEntry>,
+}
+
+impl PolyglotBook {
+    /// Load a `.bin` book file.
+    pub fn load(path: &str) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+
+        let mut entries = Vec::with_capacity(buf.len() / 16);
+        for chunk in buf.chunks_exact(16) {
+            entries.push(BookEntry {
+                key: u64::from_be_bytes(chunk[0..8].try_into().unwrap()),
+                raw_move: u16::from_be_bytes(chunk[8..10].try_into().unwrap()),
+                weight: u16::from_be_bytes(chunk[10..12].try_into().unwrap()),
+                learn: u32::from_be_bytes(chunk[12..16].try_into().unwrap()),
+            });
+        }
+        Ok(PolyglotBook { entries })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Raw entries for `key`, sorted by descending weight (PolyGlot's own
+    /// "most popular move first" convention). The book is key-sorted, so this
+    /// is a binary search plus a scan over the (usually short) run of entries
+    /// sharing that key.
+    fn entries_for(&self, key: u64) -> Vec<BookEntry> {
+        let start = self.entries.partition_point(|e| e.key < key);
+        let mut matches: Vec<BookEntry> = self.entries[start..]
+            .iter()
+            .take_while(|e| e.key == key)
+            .copied()
+            .collect();
+        matches.sort_by_key(|e| std::cmp::Reverse(e.weight));
+        matches
+    }
+
+    /// Book moves for `board`'s current position, each decoded against the
+    /// board and paired with its weight so the caller can choose among them
+    /// (e.g. weighted-random, or always the heaviest).
+    pub fn moves(&self, board: &Board) -> Vec<(Move, u16)> {
+        self.entries_for(polyglot_hash(board))
+            .into_iter()
+            .map(|e| (e.decode_move(board), e.weight))
+            .collect()
+    }
+}
@@ -0,0 +1,241 @@
+// Spaced-repetition tactics trainer: a fixed set of built-in tactical
+// puzzles scheduled with the SM-2 algorithm (the same scheme used by Anki
+// and the original SuperMemo), persisted to a flat pipe-delimited file so
+// progress survives restarts.
+
+use std::fs;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One scheduled puzzle: a starting FEN, the solving move in LAN, and this
+/// card's SM-2 state (ease factor, repetition count, interval, due time).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TacticCard {
+    pub fen: String,
+    pub solution: String,
+    pub ease_factor: f64,
+    pub repetitions: u32,
+    pub interval_days: u32,
+    pub due: u64,
+}
+
+impl TacticCard {
+    fn new(fen: &str, solution: &str) -> Self {
+        Self {
+            fen: fen.to_string(),
+            solution: solution.to_string(),
+            ease_factor: 2.5,
+            repetitions: 0,
+            interval_days: 1,
+            due: 0,
+        }
+    }
+
+    /// Apply the SM-2 algorithm for a recall-quality grade `q` (0..=5),
+    /// scheduling the next review and updating the ease factor.
+    pub fn grade(&mut self, q: u32, now: u64) {
+        if q >= 3 {
+            self.interval_days = if self.repetitions == 0 {
+                1
+            } else if self.repetitions == 1 {
+                6
+            } else {
+                (self.interval_days as f64 * self.ease_factor).round() as u32
+            };
+            self.repetitions += 1;
+        } else {
+            self.repetitions = 0;
+            self.interval_days = 1;
+        }
+
+        let q = q as f64;
+        self.ease_factor += 0.1 - (5.0 - q) * (0.08 + (5.0 - q) * 0.02);
+        if self.ease_factor < 1.3 {
+            self.ease_factor = 1.3;
+        }
+
+        self.due = now + self.interval_days as u64 * 86_400;
+    }
+
+    fn serialize(&self) -> String {
+        format!(
+            "{}|{}|{}|{}|{}|{}",
+            self.ease_factor, self.repetitions, self.interval_days, self.due, self.fen, self.solution
+        )
+    }
+
+    fn parse(line: &str) -> Option<TacticCard> {
+        let mut parts = line.splitn(6, '|');
+        let ease_factor = parts.next()?.parse().ok()?;
+        let repetitions = parts.next()?.parse().ok()?;
+        let interval_days = parts.next()?.parse().ok()?;
+        let due = parts.next()?.parse().ok()?;
+        let fen = parts.next()?.to_string();
+        let solution = parts.next()?.to_string();
+        Some(TacticCard {
+            fen,
+            solution,
+            ease_factor,
+            repetitions,
+            interval_days,
+            due,
+        })
+    }
+}
+
+/// A deck of tactics cards with SM-2 scheduling, loaded from (and saved
+/// back to) a progress file.
+pub struct TacticsTrainer {
+    cards: Vec<TacticCard>,
+}
+
+impl TacticsTrainer {
+    /// Built-in puzzle set; all are due immediately until reviewed at least once.
+    fn builtin_deck() -> Vec<TacticCard> {
+        vec![
+            // Back-rank mate: 1.Re8#
+            TacticCard::new("6k1/5ppp/8/8/8/8/8/4R2K w - - 0 1", "e1e8"),
+            // Scholar's mate: 4.Qxf7#
+            TacticCard::new(
+                "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4",
+                "h5f7",
+            ),
+            // Knight fork on king and rook: 1.Nd6+
+            TacticCard::new("2r1k3/8/8/8/4N3/8/8/6K1 w - - 0 1", "e4d6"),
+        ]
+    }
+
+    /// Load persisted progress from `path`, falling back to the built-in deck
+    /// (all due immediately) if the file is missing, empty, or unparseable.
+    pub fn load(path: &str) -> Self {
+        let cards = fs::read_to_string(path)
+            .ok()
+            .map(|text| text.lines().filter_map(TacticCard::parse).collect::<Vec<_>>())
+            .filter(|cards| !cards.is_empty())
+            .unwrap_or_else(Self::builtin_deck);
+
+        Self { cards }
+    }
+
+    /// Persist the deck's current SM-2 state to `path`.
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let text = self
+            .cards
+            .iter()
+            .map(TacticCard::serialize)
+            .collect::<Vec<_>>()
+            .join("\n");
+        fs::write(path, text)
+    }
+
+    pub fn cards(&self) -> &[TacticCard] {
+        &self.cards
+    }
+
+    /// Indices of cards due at or before `now`, earliest-due first.
+    pub fn due_indices(&self, now: u64) -> Vec<usize> {
+        let mut due: Vec<usize> = (0..self.cards.len())
+            .filter(|&i| self.cards[i].due <= now)
+            .collect();
+        due.sort_by_key(|&i| self.cards[i].due);
+        due
+    }
+
+    /// Grade the card at `index` and reschedule it.
+    pub fn grade(&mut self, index: usize, q: u32, now: u64) {
+        if let Some(card) = self.cards.get_mut(index) {
+            card.grade(q, now);
+        }
+    }
+
+    pub fn now_unix() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_correct_review_schedules_one_day() {
+        let mut card = TacticCard::new("fen", "e2e4");
+        card.grade(5, 1000);
+        assert_eq!(card.repetitions, 1);
+        assert_eq!(card.interval_days, 1);
+        assert_eq!(card.due, 1000 + 86_400);
+    }
+
+    #[test]
+    fn second_correct_review_schedules_six_days() {
+        let mut card = TacticCard::new("fen", "e2e4");
+        card.grade(5, 0);
+        card.grade(4, 0);
+        assert_eq!(card.repetitions, 2);
+        assert_eq!(card.interval_days, 6);
+    }
+
+    #[test]
+    fn third_correct_review_scales_by_ease_factor() {
+        let mut card = TacticCard::new("fen", "e2e4");
+        card.grade(5, 0);
+        card.grade(5, 0);
+        let ef_before_third = card.ease_factor;
+        card.grade(5, 0);
+        assert_eq!(card.repetitions, 3);
+        assert_eq!(card.interval_days, (6.0 * ef_before_third).round() as u32);
+    }
+
+    #[test]
+    fn failing_a_review_resets_repetitions_and_interval() {
+        let mut card = TacticCard::new("fen", "e2e4");
+        card.grade(5, 0);
+        card.grade(5, 0);
+        card.grade(1, 0);
+        assert_eq!(card.repetitions, 0);
+        assert_eq!(card.interval_days, 1);
+    }
+
+    #[test]
+    fn ease_factor_never_drops_below_floor() {
+        let mut card = TacticCard::new("fen", "e2e4");
+        for _ in 0..20 {
+            card.grade(0, 0);
+        }
+        assert!(card.ease_factor >= 1.3);
+    }
+
+    #[test]
+    fn due_indices_are_sorted_earliest_first() {
+        let trainer = TacticsTrainer {
+            cards: vec![
+                TacticCard {
+                    due: 300,
+                    ..TacticCard::new("a", "a1a2")
+                },
+                TacticCard {
+                    due: 100,
+                    ..TacticCard::new("b", "b1b2")
+                },
+                TacticCard {
+                    due: 500,
+                    ..TacticCard::new("c", "c1c2")
+                },
+            ],
+        };
+        assert_eq!(trainer.due_indices(1000), vec![1, 0, 2]);
+        assert_eq!(trainer.due_indices(200), vec![1]);
+    }
+
+    #[test]
+    fn round_trip_through_serialize_and_parse() {
+        let mut card = TacticCard::new("6k1/5ppp/8/8/8/8/8/4R2K w - - 0 1", "e1e8");
+        card.grade(4, 12345);
+        let line = card.serialize();
+        let parsed = TacticCard::parse(&line).expect("line should parse");
+        assert_eq!(parsed, card);
+    }
+}
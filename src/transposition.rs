@@ -1,26 +1,44 @@
 //! Transposition Table (TT) for the chess engine.
 //!
 //! Features:
-//! - Table sized in MB (approximate), rounded to nearest power-of-two bucket count
-//! - Each bucket stores a single entry (simple direct-mapped). Replacement policy:
-//!     prefer deeper entries, break ties by age (older entries replaced first).
+//! - Table sized in MB (approximate), rounded down to a power-of-two cluster count
+//! - Each index is a `CLUSTER_SIZE`-entry cluster (sized to fit a 64-byte cache
+//!   line) rather than a single slot, so two positions that collide on
+//!   `index_of` don't automatically evict each other. Replacement within a
+//!   cluster prefers an empty slot, then the entry with the lowest
+//!   depth/age score (see `store`).
 //! - Node types: Exact, LowerBound (Beta), UpperBound (Alpha).
 //! - Stores best move (for PV), depth, value, key, and an 8-bit age stamp.
 //! - Probe returns:
 //!     - Option<i32> when entry provides a usable score right away (alpha-beta cutoff / exact)
 //!     - Otherwise returns Option<&TTEntry> for caller to inspect.
 //! - Stats: probes, hits, stores.
-//! - Save / load to compact binary file.
+//! - Save / load to binary file, either dense (`save_to_file`/`load_from_file`,
+//!   `TTA2`) or sparse and optionally LZ4-compressed (`save_to_file_compact`/
+//!   `load_from_file_compact`, `TTA3`) - the latter is far smaller for a
+//!   mostly-empty table such as a shipped opening-book/endgame snapshot.
 //!
 //! Usage:
 //! - Create via `TranspositionTable::new_strict_size_mb(mb)` or `::new_buckets(count)`.
 //! - On new search call `tt.new_search()` to increment age.
-//! - On each node: `tt.probe(key, depth, alpha, beta)` (use returned `ProbeResult`).
-//! - After evaluating: `tt.store(key, depth, value, node_type, best_move)`.
+//! - On each node: `tt.probe(key, depth, alpha, beta, zob, ply)` (use returned `ProbeResult`).
+//! - After evaluating: `tt.store(key, depth, value, node_type, best_move, ply)`.
+//!
+//! For multi-threaded (Lazy SMP) search, `LocklessTranspositionTable` offers
+//! the same probe/store shape behind `&self` instead of `&mut self`, so
+//! several threads can share one table via `Arc` with no mutex - see its
+//! own docs below.
+//!
+//! For a table that outlives the process and can exceed RAM,
+//! `MmapTranspositionTable` backs its entries with a memory-mapped file
+//! instead of a heap `Vec` - see its own docs below.
 
+use crate::zobrist::Zobrist;
+use lz4_flex::block::{compress_prepend_size, decompress_size_prepended};
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
 
 /// The kind of node stored in the TT entry.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -91,11 +109,13 @@ impl PackedMove {
 
 /// TT Entry stored in the table.
 ///
-/// Stored compactly. Uses u64 key (Zobrist), i32 value, i32 depth, one-byte age and node type,
-/// and a PackedMove for best move.
+/// Stored compactly: rather than the full 64-bit Zobrist key, only its upper
+/// 16 bits (`key_check`) are kept for lock verification - the bucket index
+/// already pins down the rest of the key, so `key_check` only has to tell
+/// apart the (rare) other positions that hash to the same bucket.
 #[derive(Clone, Copy, Debug)]
 pub struct TTEntry {
-    pub key: u64,
+    pub key_check: u16,
     pub value: i32,
     pub depth: i32,
     pub node: NodeType,
@@ -107,7 +127,7 @@ impl TTEntry {
     /// Empty (invalid) entry marker
     pub fn empty() -> Self {
         TTEntry {
-            key: 0,
+            key_check: 0,
             value: 0,
             depth: -1,
             node: NodeType::Exact,
@@ -121,6 +141,155 @@ impl TTEntry {
     }
 }
 
+/// Upper 16 bits of a Zobrist key, used as `TTEntry::key_check`.
+#[inline]
+fn key_check_of(key: u64) -> u16 {
+    (key >> 48) as u16
+}
+
+/// Issue a software prefetch of the cache line containing `*ptr`, hinting the
+/// CPU to start pulling it in before it's actually read. A pure latency-hiding
+/// hint: it never affects correctness, so targets without an intrinsic for it
+/// just no-op.
+#[inline]
+fn prefetch_ptr<T>(ptr: *const T) {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        std::arch::x86_64::_mm_prefetch(ptr as *const i8, std::arch::x86_64::_MM_HINT_T0);
+    }
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        std::arch::aarch64::_prefetch(
+            ptr as *const i8,
+            std::arch::aarch64::_PREFETCH_READ,
+            std::arch::aarch64::_PREFETCH_LOCALITY3,
+        );
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        let _ = ptr;
+    }
+}
+
+/// Byte length of one serialized `TTEntry` (see `write_entry_bytes`).
+const ENTRY_BYTES: usize = 16;
+
+/// Append `e`'s fields to `buf` in the same layout `save_to_file`/
+/// `save_to_file_compact` have always used on disk.
+fn write_entry_bytes(buf: &mut Vec<u8>, e: &TTEntry) {
+    buf.extend_from_slice(&e.key_check.to_le_bytes());
+    buf.extend_from_slice(&e.value.to_le_bytes());
+    buf.extend_from_slice(&e.depth.to_le_bytes());
+    buf.push(u8::from(e.node));
+    buf.push(e.age);
+    buf.extend_from_slice(&e.best.0.to_le_bytes());
+}
+
+/// Inverse of `write_entry_bytes`; `bytes` must be exactly `ENTRY_BYTES` long.
+fn read_entry_bytes(bytes: &[u8]) -> TTEntry {
+    TTEntry {
+        key_check: u16::from_le_bytes(bytes[0..2].try_into().unwrap()),
+        value: i32::from_le_bytes(bytes[2..6].try_into().unwrap()),
+        depth: i32::from_le_bytes(bytes[6..10].try_into().unwrap()),
+        node: NodeType::from(bytes[10]),
+        age: bytes[11],
+        best: PackedMove(u32::from_le_bytes(bytes[12..16].try_into().unwrap())),
+    }
+}
+
+/// Score magnitude of a confirmed checkmate, matching the mate scores
+/// `negamax` returns at a terminal node.
+pub const MATE: i32 = 100000;
+/// Search depth past which a stored mate distance can no longer be trusted
+/// to stay clear of ordinary evaluation scores.
+pub const MAX_PLY: i32 = 128;
+/// Scores at or beyond this magnitude are mate distances, not ordinary
+/// evaluations, and need the `ply` correction in `store`/`probe` rather than
+/// being cached verbatim (a mate found 5 plies down a branch reached at ply
+/// 10 is a mate-in-15 from the root, not mate-in-5).
+pub const MATE_BOUND: i32 = MATE - MAX_PLY;
+
+/// Convert a root-relative mate score (as returned by the search) into a
+/// node-relative one fit for storage, so the same TT entry is still valid
+/// when this position is reached again at a different ply. Non-mate scores
+/// pass through unchanged.
+#[inline]
+fn to_tt_value(value: i32, ply: i32) -> i32 {
+    if value >= MATE_BOUND {
+        value + ply
+    } else if value <= -MATE_BOUND {
+        value - ply
+    } else {
+        value
+    }
+}
+
+/// Inverse of `to_tt_value`: convert a stored node-relative mate score back
+/// to root-relative before handing it to the caller.
+#[inline]
+fn from_tt_value(value: i32, ply: i32) -> i32 {
+    if value >= MATE_BOUND {
+        value - ply
+    } else if value <= -MATE_BOUND {
+        value + ply
+    } else {
+        value
+    }
+}
+
+/// Decide how a matched `entry` can be used against the requested `depth`
+/// and alpha/beta window. Shared by `TranspositionTable::probe` and
+/// `LocklessTranspositionTable::probe` so both tables apply identical
+/// alpha-beta cutoff rules once a matching entry is found. `ply` undoes the
+/// mate-distance shift `store` applied, so the comparisons and the value
+/// handed back are both root-relative again.
+fn usable_result(entry: TTEntry, depth: i32, alpha: i32, beta: i32, ply: i32) -> ProbeResult {
+    let entry = TTEntry {
+        value: from_tt_value(entry.value, ply),
+        ..entry
+    };
+    if entry.depth < depth {
+        // depth insufficient: return entry for ordering info (e.g., PV move)
+        return ProbeResult::Found(entry);
+    }
+    match entry.node {
+        NodeType::Exact => ProbeResult::Usable(entry.value, Some(entry.best)),
+        NodeType::LowerBound => {
+            // stored value is a lower bound: usable if value >= beta
+            if entry.value >= beta {
+                ProbeResult::Usable(entry.value, Some(entry.best))
+            } else {
+                ProbeResult::Found(entry)
+            }
+        }
+        NodeType::UpperBound => {
+            // stored value is an upper bound: usable if value <= alpha
+            if entry.value <= alpha {
+                ProbeResult::Usable(entry.value, Some(entry.best))
+            } else {
+                ProbeResult::Found(entry)
+            }
+        }
+    }
+}
+
+/// Entries per cluster. At 16 bytes per `TTEntry`, four slots fill exactly
+/// one 64-byte cache line, so probing or storing into a cluster touches only
+/// one line.
+pub const CLUSTER_SIZE: usize = 4;
+
+/// Weight applied to an entry's age (in search generations) when scoring it
+/// as a replacement candidate in `TranspositionTable::store`; higher values
+/// bias eviction more strongly towards stale entries over shallow-but-fresh
+/// ones.
+const REPLACE_AGE_WEIGHT: i32 = 4;
+
+/// `save_to_file_compact` flags byte: set to write only non-empty slots as
+/// `[u32 index][entry...]` records instead of every slot in order.
+const COMPACT_FLAG_SPARSE: u8 = 0b01;
+/// `save_to_file_compact` flags byte: set to LZ4-compress the entry payload.
+const COMPACT_FLAG_COMPRESSED: u8 = 0b10;
+
 /// Result of probing the table.
 #[derive(Clone, Copy, Debug)]
 pub enum ProbeResult {
@@ -135,8 +304,10 @@ pub enum ProbeResult {
 
 /// Transposition Table structure
 pub struct TranspositionTable {
+    // Flat storage of `clusters * CLUSTER_SIZE` entries; cluster `i` occupies
+    // `buckets[i * CLUSTER_SIZE .. (i + 1) * CLUSTER_SIZE]`.
     buckets: Vec<TTEntry>,
-    mask: usize, // index mask (buckets.len() - 1)
+    mask: usize, // cluster index mask (num_clusters - 1)
     pub age: u8,
 
     // Stats
@@ -148,31 +319,39 @@ pub struct TranspositionTable {
 impl TranspositionTable {
     /// Create a new table with approximately `size_mb` megabytes of storage.
     ///
-    /// We approximate size per entry and choose nearest power-of-two bucket count.
+    /// We approximate entries per megabyte and choose the nearest
+    /// power-of-two cluster count that fits, each cluster holding
+    /// `CLUSTER_SIZE` entries.
     pub fn new_strict_size_mb(size_mb: usize) -> Self {
         // Estimate bytes per entry:
-        // u64 key(8) + i32 value(4) + i32 depth(4) + u8 age(1) + u8 node(1) + PackedMove(4) + padding => ~24 bytes
-        let bytes_per_entry = 24usize.max(std::mem::size_of::<TTEntry>());
+        // u16 key_check(2) + i32 value(4) + i32 depth(4) + u8 age(1) + u8 node(1) + PackedMove(4) + padding => ~16 bytes
+        let bytes_per_entry = 16usize.max(std::mem::size_of::<TTEntry>());
         let total_bytes = size_mb * 1024 * 1024;
-        let mut buckets = total_bytes / bytes_per_entry;
-        if buckets == 0 {
-            buckets = 1;
+        let mut entries = total_bytes / bytes_per_entry;
+        if entries == 0 {
+            entries = CLUSTER_SIZE;
+        }
+        let mut clusters = entries / CLUSTER_SIZE;
+        if clusters == 0 {
+            clusters = 1;
         }
         // round down to power-of-two
-        let pow = (usize::BITS - (buckets as u32).leading_zeros() - 1) as usize;
+        let pow = (usize::BITS - (clusters as u32).leading_zeros() - 1) as usize;
         let count = 1usize << pow;
         TranspositionTable::new_buckets(count)
     }
 
-    /// Create a new table with exactly `buckets` entries (must be power-of-two for mask).
-    pub fn new_buckets(buckets: usize) -> Self {
-        assert!(buckets >= 1, "buckets must be >= 1");
+    /// Create a new table with exactly `clusters` cluster slots (rounded up
+    /// to a power of two for the index mask), each holding `CLUSTER_SIZE`
+    /// entries.
+    pub fn new_buckets(clusters: usize) -> Self {
+        assert!(clusters >= 1, "clusters must be >= 1");
         // ensure power of two; if not, round up
         let mut count = 1usize;
-        while count < buckets {
+        while count < clusters {
             count <<= 1;
         }
-        let vec = vec![TTEntry::empty(); count];
+        let vec = vec![TTEntry::empty(); count * CLUSTER_SIZE];
         TranspositionTable {
             buckets: vec,
             mask: count - 1,
@@ -183,12 +362,24 @@ impl TranspositionTable {
         }
     }
 
-    /// Simple index function: use lower bits of key xor-shifted.
+    /// Index of the first entry in `key`'s cluster: fold the key down to the
+    /// cluster-index bits, then scale up to its base offset into the flat
+    /// `buckets` vec.
     #[inline]
     fn index_of(&self, key: u64) -> usize {
         // xor-fold to reduce clustering
         let folded = key ^ (key >> 32) ^ (key >> 16);
-        (folded as usize) & self.mask
+        ((folded as usize) & self.mask) * CLUSTER_SIZE
+    }
+
+    /// Hint that `key`'s cluster will be probed/stored soon, so the cache
+    /// line can be warmed while the caller is still doing other work (e.g.
+    /// right after making a move, before recursing into the resulting
+    /// position). Pure performance hint - never changes behavior.
+    #[inline]
+    pub fn prefetch(&self, key: u64) {
+        let idx = self.index_of(key);
+        prefetch_ptr(unsafe { self.buckets.as_ptr().add(idx) });
     }
 
     /// Called at the start of a fresh search iteration to age entries.
@@ -202,58 +393,72 @@ impl TranspositionTable {
 
     /// Probe table for `key` with `depth` and alpha/beta window.
     ///
+    /// Scans every slot in `key`'s cluster for a matching `key_check` before
+    /// declaring `Miss` - with `CLUSTER_SIZE` slots per index, two colliding
+    /// positions can coexist rather than evicting each other.
+    ///
     /// If an entry is usable to return a value immediately according to alpha-beta rules,
     /// returns `ProbeResult::Usable(value, best_move_opt)`.
     /// If entry present but not immediately usable, returns `ProbeResult::Found(entry)`.
     /// If no entry, returns `ProbeResult::Miss`.
     ///
     /// Note: `alpha` and `beta` follow standard negamax bounds semantics.
-    pub fn probe(&mut self, key: u64, depth: i32, alpha: i32, beta: i32) -> ProbeResult {
+    ///
+    /// `zob` is only used to record a hit in `ZobristStats.cluster_contention`
+    /// when every slot in the cluster is occupied by a different position
+    /// (its `key_check` doesn't match ours). That's ordinary bucket
+    /// contention as the table fills up, not a genuine 64-bit hash
+    /// collision, so it's tracked separately from `collisions_detected`.
+    ///
+    /// `ply` is the current node's distance from the search root, used to
+    /// re-derive a root-relative mate score from whatever distance-from-node
+    /// value `store` saved (see `from_tt_value`).
+    pub fn probe(
+        &mut self,
+        key: u64,
+        depth: i32,
+        alpha: i32,
+        beta: i32,
+        zob: &mut Zobrist,
+        ply: i32,
+    ) -> ProbeResult {
         self.probes = self.probes.wrapping_add(1);
-        let idx = self.index_of(key);
-        let entry = self.buckets[idx];
-        if entry.is_empty() || entry.key != key {
-            return ProbeResult::Miss;
-        }
-        // key matches
-        self.hits = self.hits.wrapping_add(1);
+        let base = self.index_of(key);
+        let check = key_check_of(key);
+        let mut any_occupied = false;
 
-        // If stored entry depth is >= required depth, we may use the entry
-        if entry.depth >= depth {
-            match entry.node {
-                NodeType::Exact => {
-                    return ProbeResult::Usable(entry.value, Some(entry.best));
-                }
-                NodeType::LowerBound => {
-                    // stored value is a lower bound: usable if value >= beta
-                    if entry.value >= beta {
-                        return ProbeResult::Usable(entry.value, Some(entry.best));
-                    } else {
-                        return ProbeResult::Found(entry);
-                    }
-                }
-                NodeType::UpperBound => {
-                    // stored value is an upper bound: usable if value <= alpha
-                    if entry.value <= alpha {
-                        return ProbeResult::Usable(entry.value, Some(entry.best));
-                    } else {
-                        return ProbeResult::Found(entry);
-                    }
-                }
+        for slot in &self.buckets[base..base + CLUSTER_SIZE] {
+            if slot.is_empty() {
+                continue;
             }
-        } else {
-            // depth insufficient: return entry for ordering info (e.g., PV move)
-            return ProbeResult::Found(entry);
+            any_occupied = true;
+            if slot.key_check != check {
+                continue;
+            }
+
+            // key_check matches
+            self.hits = self.hits.wrapping_add(1);
+            return usable_result(*slot, depth, alpha, beta, ply);
+        }
+
+        if any_occupied {
+            zob.record_cluster_contention();
         }
+        ProbeResult::Miss
     }
 
-    /// Store an entry into the table with replacement policy.
+    /// Store an entry into `key`'s cluster:
+    /// - If a slot already holds `key`, refresh it in place.
+    /// - Otherwise, prefer an empty slot.
+    /// - Otherwise, evict the slot with the lowest combined depth/age score
+    ///   (`entry.depth - relative_age(entry.age) * REPLACE_AGE_WEIGHT`, where
+    ///   `relative_age` is generations since the entry was stored), so deep
+    ///   recent entries survive while shallow or stale ones get recycled.
     ///
-    /// Replacement heuristic:
-    /// - If the slot is empty -> place entry
-    /// - Else if new.depth > old.depth -> replace
-    /// - Else if ages differ -> replace older entry (so newer searches prefer newer data)
-    /// - Else replace (tie-break)
+    /// `ply` is the current node's distance from the search root; mate
+    /// scores are normalized to a distance-from-this-node value before
+    /// storage (see `to_tt_value`) so the entry stays correct if this
+    /// position is reached again at a different ply.
     pub fn store(
         &mut self,
         key: u64,
@@ -261,61 +466,52 @@ impl TranspositionTable {
         value: i32,
         node: NodeType,
         best_move: Option<(usize, usize, u8)>, // (from, to, promo_id) packed here
+        ply: i32,
     ) {
         self.stores = self.stores.wrapping_add(1);
-        let idx = self.index_of(key);
-        let old = self.buckets[idx];
+        let base = self.index_of(key);
+        let check = key_check_of(key);
         let mut packed = PackedMove::none();
         if let Some((from, to, promo_id)) = best_move {
             packed = PackedMove::pack(from, to, promo_id);
         }
 
         let new_entry = TTEntry {
-            key,
-            value,
+            key_check: check,
+            value: to_tt_value(value, ply),
             depth,
             node,
             age: self.age,
             best: packed,
         };
 
-        // Decide replacement
-        let replace = if old.is_empty() {
-            true
-        } else if new_entry.depth > old.depth {
-            true
-        } else if new_entry.depth == old.depth {
-            // prefer newer age
-            if new_entry.age != old.age {
-                true // replace older with newer
-            } else {
-                // equal depth and age -> prefer Exact > LowerBound > UpperBound
-                match (new_entry.node, old.node) {
-                    (NodeType::Exact, NodeType::Exact) => true, // update best move / value
-                    (NodeType::Exact, _) => true,
-                    (NodeType::LowerBound, NodeType::UpperBound) => true,
-                    (_, _) => false,
-                }
+        for i in 0..CLUSTER_SIZE {
+            let slot = self.buckets[base + i];
+            if !slot.is_empty() && slot.key_check == check {
+                self.buckets[base + i] = new_entry;
+                return;
             }
-        } else {
-            // new.depth < old.depth -> only replace if old is very old (age) or same key collision
-            if old.age != self.age {
-                // old is from older search -> replace
-                true
-            } else {
-                // do not replace
-                false
+        }
+
+        for i in 0..CLUSTER_SIZE {
+            if self.buckets[base + i].is_empty() {
+                self.buckets[base + i] = new_entry;
+                return;
             }
-        };
+        }
 
-        if replace {
-            self.buckets[idx] = new_entry;
-        } else {
-            // not replacing; however, if same key we might update the best move/value if deeper/equal
-            if old.key == key && new_entry.depth >= old.depth {
-                self.buckets[idx] = new_entry;
+        let mut victim = 0usize;
+        let mut victim_score = i32::MAX;
+        for i in 0..CLUSTER_SIZE {
+            let e = self.buckets[base + i];
+            let relative_age = self.age.wrapping_sub(e.age) as i32;
+            let score = e.depth - relative_age * REPLACE_AGE_WEIGHT;
+            if score < victim_score {
+                victim_score = score;
+                victim = i;
             }
         }
+        self.buckets[base + victim] = new_entry;
     }
 
     /// Force clear the TT (set all entries empty).
@@ -329,17 +525,18 @@ impl TranspositionTable {
         self.stores = 0;
     }
 
-    /// Dump the table to a binary file. Format:
+    /// Dump the table to a binary file. Format (v2, `key_check` replacing the
+    /// full key):
     /// [u64: magic][u32:buckets][entries...]
     /// Each entry serialized as:
-    /// [u64 key][i32 value][i32 depth][u8 node][u8 age][u32 packed_move]
+    /// [u16 key_check][i32 value][i32 depth][u8 node][u8 age][u32 packed_move]
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
         let mut f = File::create(path)?;
-        f.write_all(&0x54544142u64.to_le_bytes())?; // 'TTAB' magic
+        f.write_all(&0x54544132u64.to_le_bytes())?; // 'TTA2' magic
         let cnt = self.buckets.len() as u32;
         f.write_all(&cnt.to_le_bytes())?;
         for e in &self.buckets {
-            f.write_all(&e.key.to_le_bytes())?;
+            f.write_all(&e.key_check.to_le_bytes())?;
             f.write_all(&e.value.to_le_bytes())?;
             f.write_all(&e.depth.to_le_bytes())?;
             f.write_all(&u8::from(e.node).to_le_bytes())?;
@@ -355,7 +552,7 @@ impl TranspositionTable {
         let mut buf8 = [0u8; 8];
         f.read_exact(&mut buf8)?;
         let magic = u64::from_le_bytes(buf8);
-        if magic != 0x54544142u64 {
+        if magic != 0x54544132u64 {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 "bad magic",
@@ -371,9 +568,9 @@ impl TranspositionTable {
             ));
         }
         for e in self.buckets.iter_mut() {
-            let mut b8 = [0u8; 8];
-            f.read_exact(&mut b8)?;
-            let key = u64::from_le_bytes(b8);
+            let mut b2 = [0u8; 2];
+            f.read_exact(&mut b2)?;
+            let key_check = u16::from_le_bytes(b2);
             let mut i4 = [0u8; 4];
             f.read_exact(&mut i4)?;
             let value = i32::from_le_bytes(i4);
@@ -388,7 +585,7 @@ impl TranspositionTable {
             f.read_exact(&mut b4)?;
             let packed = u32::from_le_bytes(b4);
             *e = TTEntry {
-                key,
+                key_check,
                 value,
                 depth,
                 node,
@@ -399,6 +596,146 @@ impl TranspositionTable {
         Ok(())
     }
 
+    /// Dump the table to a compact binary file (v3, `TTA3` magic - not
+    /// readable by `load_from_file`, which only understands the older `TTA2`
+    /// dense format). Format:
+    /// [u64 magic][u32 total_entries][u8 flags][sparse? u32 live_count][payload]
+    ///
+    /// `flags` bit 0 set means `payload` lists only non-empty slots as
+    /// `[u32 index][entry bytes...]` records (good for mostly-empty tables,
+    /// e.g. opening-book snapshots); unset means every slot is written in
+    /// order with no index prefix, same as `save_to_file`. `flags` bit 1 set
+    /// means `payload` is LZ4-compressed (via `lz4_flex::compress_prepend_size`,
+    /// which embeds the decompressed length needed by `decompress_size_prepended`).
+    pub fn save_to_file_compact<P: AsRef<Path>>(
+        &self,
+        path: P,
+        sparse: bool,
+        compress: bool,
+    ) -> std::io::Result<()> {
+        let mut f = File::create(path)?;
+        f.write_all(&0x54544133u64.to_le_bytes())?; // 'TTA3' magic
+        let total = self.buckets.len() as u32;
+        f.write_all(&total.to_le_bytes())?;
+
+        let mut flags = 0u8;
+        if sparse {
+            flags |= COMPACT_FLAG_SPARSE;
+        }
+        if compress {
+            flags |= COMPACT_FLAG_COMPRESSED;
+        }
+        f.write_all(&[flags])?;
+
+        let mut payload = Vec::new();
+        if sparse {
+            let live_count = self.buckets.iter().filter(|e| !e.is_empty()).count() as u32;
+            f.write_all(&live_count.to_le_bytes())?;
+            for (idx, e) in self.buckets.iter().enumerate() {
+                if e.is_empty() {
+                    continue;
+                }
+                payload.extend_from_slice(&(idx as u32).to_le_bytes());
+                write_entry_bytes(&mut payload, e);
+            }
+        } else {
+            for e in &self.buckets {
+                write_entry_bytes(&mut payload, e);
+            }
+        }
+
+        if compress {
+            f.write_all(&compress_prepend_size(&payload))?;
+        } else {
+            f.write_all(&payload)?;
+        }
+        Ok(())
+    }
+
+    /// Load a table saved by `save_to_file_compact`. Rejects anything that
+    /// isn't a `TTA3` dump (including old `TTA2` dumps from `save_to_file`)
+    /// with an `InvalidData` error rather than trying to guess the format.
+    pub fn load_from_file_compact<P: AsRef<Path>>(&mut self, path: P) -> std::io::Result<()> {
+        let mut f = File::open(path)?;
+        let mut buf8 = [0u8; 8];
+        f.read_exact(&mut buf8)?;
+        let magic = u64::from_le_bytes(buf8);
+        if magic != 0x54544133u64 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "bad magic",
+            ));
+        }
+        let mut buf4 = [0u8; 4];
+        f.read_exact(&mut buf4)?;
+        let total = u32::from_le_bytes(buf4) as usize;
+        if total != self.buckets.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "bucket count mismatch",
+            ));
+        }
+        let mut flag_buf = [0u8; 1];
+        f.read_exact(&mut flag_buf)?;
+        let flags = flag_buf[0];
+        let sparse = flags & COMPACT_FLAG_SPARSE != 0;
+        let compressed = flags & COMPACT_FLAG_COMPRESSED != 0;
+
+        let live_count = if sparse {
+            f.read_exact(&mut buf4)?;
+            Some(u32::from_le_bytes(buf4) as usize)
+        } else {
+            None
+        };
+
+        let mut rest = Vec::new();
+        f.read_to_end(&mut rest)?;
+        let payload = if compressed {
+            decompress_size_prepended(&rest).map_err(|e| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())
+            })?
+        } else {
+            rest
+        };
+
+        for e in self.buckets.iter_mut() {
+            *e = TTEntry::empty();
+        }
+
+        if sparse {
+            let live_count = live_count.unwrap();
+            let record_bytes = 4 + ENTRY_BYTES;
+            if payload.len() < live_count * record_bytes {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "truncated sparse payload",
+                ));
+            }
+            for i in 0..live_count {
+                let rec = &payload[i * record_bytes..(i + 1) * record_bytes];
+                let idx = u32::from_le_bytes(rec[0..4].try_into().unwrap()) as usize;
+                if idx >= self.buckets.len() {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "index out of range",
+                    ));
+                }
+                self.buckets[idx] = read_entry_bytes(&rec[4..]);
+            }
+        } else {
+            if payload.len() < total * ENTRY_BYTES {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "truncated dense payload",
+                ));
+            }
+            for (i, chunk) in payload.chunks_exact(ENTRY_BYTES).take(total).enumerate() {
+                self.buckets[i] = read_entry_bytes(chunk);
+            }
+        }
+        Ok(())
+    }
+
     /// Return stats snapshot as a human-readable string.
     pub fn stats(&self) -> String {
         format!(
@@ -415,20 +752,369 @@ impl TranspositionTable {
         )
     }
 
+    /// Estimate how full the table is, in permille (0..=1000), the way UCI's
+    /// `info hashfull` reports it: sample the first 1000 buckets (or all of
+    /// them, if there are fewer) and count how many hold an entry from the
+    /// current search generation.
+    pub fn hashfull(&self) -> u32 {
+        let sample = self.buckets.len().min(1000);
+        if sample == 0 {
+            return 0;
+        }
+        let filled = self.buckets[..sample]
+            .iter()
+            .filter(|e| !e.is_empty() && e.age == self.age)
+            .count();
+        ((filled * 1000) / sample) as u32
+    }
+
     /// Return the best move stored for a given key, if present.
     pub fn best_move_for(&self, key: u64) -> Option<(usize, usize, u8)> {
+        let base = self.index_of(key);
+        let check = key_check_of(key);
+        for slot in &self.buckets[base..base + CLUSTER_SIZE] {
+            if slot.is_empty() || slot.key_check != check {
+                continue;
+            }
+            if slot.best == PackedMove::none() {
+                return None;
+            }
+            let (f, t, p) = slot.best.unpack();
+            return Some((f, t, p));
+        }
+        None
+    }
+}
+
+// =====================
+// Lockless Concurrent Table (Lazy SMP)
+// =====================
+
+/// An entry's non-key fields (`value`, `depth`, `node`, `age`, `best`) packed
+/// into a single `u64`, plus one bit marking the slot occupied. Used only by
+/// `LocklessTranspositionTable`'s XOR-tagged slots; unrelated to `TTEntry`'s
+/// own in-memory layout.
+const LOCKLESS_OCCUPIED_BIT: u64 = 1 << 63;
+const LOCKLESS_MOVE_BITS: u32 = 18; // PackedMove only ever sets bits 0..17
+const LOCKLESS_NODE_BITS: u32 = 2;
+const LOCKLESS_AGE_BITS: u32 = 8;
+const LOCKLESS_DEPTH_BITS: u32 = 7; // search depth comfortably fits in 0..127
+const LOCKLESS_VALUE_BITS: u32 = 28; // plenty of headroom over mate-range scores
+
+const LOCKLESS_MOVE_SHIFT: u32 = 0;
+const LOCKLESS_NODE_SHIFT: u32 = LOCKLESS_MOVE_SHIFT + LOCKLESS_MOVE_BITS;
+const LOCKLESS_AGE_SHIFT: u32 = LOCKLESS_NODE_SHIFT + LOCKLESS_NODE_BITS;
+const LOCKLESS_DEPTH_SHIFT: u32 = LOCKLESS_AGE_SHIFT + LOCKLESS_AGE_BITS;
+const LOCKLESS_VALUE_SHIFT: u32 = LOCKLESS_DEPTH_SHIFT + LOCKLESS_DEPTH_BITS;
+
+fn pack_lockless_data(entry: &TTEntry) -> u64 {
+    let move_bits = (entry.best.0 as u64) & ((1 << LOCKLESS_MOVE_BITS) - 1);
+    let node_bits = (u8::from(entry.node) as u64) & ((1 << LOCKLESS_NODE_BITS) - 1);
+    let age_bits = (entry.age as u64) & ((1 << LOCKLESS_AGE_BITS) - 1);
+    let depth_bits = (entry.depth as u64) & ((1 << LOCKLESS_DEPTH_BITS) - 1);
+    let value_bits = (entry.value as u32 as u64) & ((1 << LOCKLESS_VALUE_BITS) - 1);
+
+    LOCKLESS_OCCUPIED_BIT
+        | (move_bits << LOCKLESS_MOVE_SHIFT)
+        | (node_bits << LOCKLESS_NODE_SHIFT)
+        | (age_bits << LOCKLESS_AGE_SHIFT)
+        | (depth_bits << LOCKLESS_DEPTH_SHIFT)
+        | (value_bits << LOCKLESS_VALUE_SHIFT)
+}
+
+fn unpack_lockless_data(data: u64) -> TTEntry {
+    let move_mask = (1u64 << LOCKLESS_MOVE_BITS) - 1;
+    let node_mask = (1u64 << LOCKLESS_NODE_BITS) - 1;
+    let age_mask = (1u64 << LOCKLESS_AGE_BITS) - 1;
+    let depth_mask = (1u64 << LOCKLESS_DEPTH_BITS) - 1;
+    let value_mask = (1u64 << LOCKLESS_VALUE_BITS) - 1;
+
+    let best = PackedMove(((data >> LOCKLESS_MOVE_SHIFT) & move_mask) as u32);
+    let node = NodeType::from(((data >> LOCKLESS_NODE_SHIFT) & node_mask) as u8);
+    let age = ((data >> LOCKLESS_AGE_SHIFT) & age_mask) as u8;
+    let depth = ((data >> LOCKLESS_DEPTH_SHIFT) & depth_mask) as i32;
+
+    // Sign-extend the value field back out from its packed width.
+    let raw_value = (data >> LOCKLESS_VALUE_SHIFT) & value_mask;
+    let sign_bit = 1u64 << (LOCKLESS_VALUE_BITS - 1);
+    let value = if raw_value & sign_bit != 0 {
+        (raw_value | !value_mask) as i64 as i32
+    } else {
+        raw_value as i32
+    };
+
+    TTEntry {
+        key_check: 0, // unused: the lockless table checks the full key, not a 16-bit prefix
+        value,
+        depth,
+        node,
+        age,
+        best,
+    }
+}
+
+/// One lockless slot, using the classic XOR-tagged trick for detecting torn
+/// concurrent writes without a lock: `key_xor_data` always holds
+/// `key ^ data`, so a reader can recompute `key` as `key_xor_data ^ data` and
+/// compare it against the key it's probing for. A write torn by a
+/// concurrently-running writer (one atomic updated, the other not yet)
+/// makes that comparison fail, which `probe` treats the same as a miss.
+struct LocklessSlot {
+    key_xor_data: AtomicU64,
+    data: AtomicU64,
+}
+
+impl LocklessSlot {
+    fn empty() -> Self {
+        LocklessSlot {
+            key_xor_data: AtomicU64::new(0),
+            data: AtomicU64::new(0),
+        }
+    }
+}
+
+/// A transposition table that can be probed and stored into from several
+/// search threads at once (Lazy SMP) with no mutex, via `LocklessSlot`'s
+/// XOR-tagged entries. Share one instance across threads with `Arc`.
+///
+/// This is a separate, simpler sibling of `TranspositionTable` (one slot per
+/// index, no clustering, no zobrist-collision stats, no save/load) rather
+/// than a drop-in replacement; the single-threaded serial path keeps using
+/// `TranspositionTable` unchanged.
+pub struct LocklessTranspositionTable {
+    slots: Vec<LocklessSlot>,
+    mask: usize,
+    age: AtomicU8,
+}
+
+impl LocklessTranspositionTable {
+    /// Create a new table with at least `buckets` slots (rounded up to a
+    /// power of two for the index mask).
+    pub fn new_buckets(buckets: usize) -> Self {
+        assert!(buckets >= 1, "buckets must be >= 1");
+        let mut count = 1usize;
+        while count < buckets {
+            count <<= 1;
+        }
+        let mut slots = Vec::with_capacity(count);
+        slots.resize_with(count, LocklessSlot::empty);
+        LocklessTranspositionTable {
+            slots,
+            mask: count - 1,
+            age: AtomicU8::new(1),
+        }
+    }
+
+    #[inline]
+    fn index_of(&self, key: u64) -> usize {
+        let folded = key ^ (key >> 32) ^ (key >> 16);
+        (folded as usize) & self.mask
+    }
+
+    /// Hint that `key`'s slot will be probed/stored soon; see
+    /// `TranspositionTable::prefetch`. Safe to call from any thread sharing
+    /// this table, same as `probe`/`store`.
+    #[inline]
+    pub fn prefetch(&self, key: u64) {
         let idx = self.index_of(key);
-        let e = self.buckets[idx];
-        if e.key != key || e.is_empty() {
-            None
-        } else {
-            let (f, t, p) = e.best.unpack();
-            if e.best == PackedMove::none() {
-                None
-            } else {
-                Some((f, t, p))
+        prefetch_ptr(unsafe { self.slots.as_ptr().add(idx) });
+    }
+
+    /// Called at the start of a fresh search iteration to age entries, same
+    /// as `TranspositionTable::new_search`.
+    pub fn new_search(&self) {
+        self.age.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Probe the table for `key`. Semantics match `TranspositionTable::probe`
+    /// (same alpha/beta cutoff rules via `usable_result`, including the
+    /// mate-distance correction driven by `ply`); the only difference is
+    /// that a torn concurrent write is indistinguishable from, and handled
+    /// identically to, a genuine miss.
+    pub fn probe(&self, key: u64, depth: i32, alpha: i32, beta: i32, ply: i32) -> ProbeResult {
+        let idx = self.index_of(key);
+        let slot = &self.slots[idx];
+
+        let data = slot.data.load(Ordering::Relaxed);
+        let key_xor_data = slot.key_xor_data.load(Ordering::Relaxed);
+
+        if data & LOCKLESS_OCCUPIED_BIT == 0 || key_xor_data ^ data != key {
+            return ProbeResult::Miss;
+        }
+
+        usable_result(unpack_lockless_data(data), depth, alpha, beta, ply)
+    }
+
+    /// Store an entry for `key`, unconditionally overwriting its slot: with
+    /// no lock to coordinate a depth/age-aware replacement policy across
+    /// threads, a blind overwrite is the standard Lazy SMP tradeoff (any
+    /// thread can re-populate a useful entry on its next visit). `ply` is
+    /// normalized into the stored value exactly as in
+    /// `TranspositionTable::store`.
+    pub fn store(
+        &self,
+        key: u64,
+        depth: i32,
+        value: i32,
+        node: NodeType,
+        best_move: Option<(usize, usize, u8)>,
+        ply: i32,
+    ) {
+        let idx = self.index_of(key);
+        let slot = &self.slots[idx];
+
+        let mut packed = PackedMove::none();
+        if let Some((from, to, promo_id)) = best_move {
+            packed = PackedMove::pack(from, to, promo_id);
+        }
+        let entry = TTEntry {
+            key_check: 0,
+            value: to_tt_value(value, ply),
+            depth,
+            node,
+            age: self.age.load(Ordering::Relaxed),
+            best: packed,
+        };
+        let data = pack_lockless_data(&entry);
+
+        // Write data first, then the XOR tag: a concurrent reader that
+        // observes the new data but the old tag (or vice versa) will fail
+        // the `key_xor_data ^ data == key` check in `probe` and back off as
+        // a miss instead of returning a torn entry.
+        slot.data.store(data, Ordering::Release);
+        slot.key_xor_data.store(key ^ data, Ordering::Release);
+    }
+}
+
+/// Magic tag identifying an `MmapTranspositionTable` backing file ('TTM1').
+const MMAP_MAGIC: u64 = 0x5454_4D31;
+/// Header size in bytes: `[u64 magic][u32 bucket_count][u32 entry_byte_size]`.
+const MMAP_HEADER_BYTES: usize = 16;
+
+/// A transposition table backed by a memory-mapped file instead of a heap
+/// `Vec`, so it can be larger than RAM (the OS pages it in on demand) and,
+/// unlike `TranspositionTable`, persists across process runs - reopening the
+/// same path with the same size picks up exactly where the last run left
+/// off, since every entry is self-identified by its stored `key_check` and
+/// `index_of` is deterministic for a given `bucket_count`. No clustering:
+/// one slot per index, with `store` simply overwriting whatever was there
+/// (mirroring the table's pre-clustering design), since the on-disk layout
+/// has to stay a fixed, directly-addressed array of fixed-width entries.
+pub struct MmapTranspositionTable {
+    mmap: memmap2::MmapMut,
+    mask: usize,
+    age: u8,
+}
+
+impl MmapTranspositionTable {
+    /// Open (creating if needed) a memory-mapped TT backed by `path`, sized
+    /// to roughly `size_mb` megabytes. If `path` already holds a table of the
+    /// matching `bucket_count`, it's reused as-is (stale entries are just
+    /// overwritten by `store` as new searches run) - otherwise the file is
+    /// (re)created and its header written fresh.
+    pub fn open_mmap<P: AsRef<Path>>(path: P, size_mb: usize) -> std::io::Result<Self> {
+        let total_bytes = size_mb.max(1) * 1024 * 1024;
+        let entries = (total_bytes / ENTRY_BYTES).max(1);
+        let mut bucket_count = 1usize;
+        while bucket_count * 2 <= entries {
+            bucket_count *= 2;
+        }
+        let file_len = (MMAP_HEADER_BYTES + bucket_count * ENTRY_BYTES) as u64;
+
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        let reuse = file.metadata()?.len() == file_len && {
+            let mut header = [0u8; MMAP_HEADER_BYTES];
+            let mut f2 = &file;
+            f2.read_exact(&mut header).is_ok()
+                && u64::from_le_bytes(header[0..8].try_into().unwrap()) == MMAP_MAGIC
+                && u32::from_le_bytes(header[8..12].try_into().unwrap()) as usize == bucket_count
+                && u32::from_le_bytes(header[12..16].try_into().unwrap()) as usize == ENTRY_BYTES
+        };
+
+        file.set_len(file_len)?;
+        let mut mmap = unsafe { memmap2::MmapMut::map_mut(&file)? };
+
+        if !reuse {
+            mmap[0..8].copy_from_slice(&MMAP_MAGIC.to_le_bytes());
+            mmap[8..12].copy_from_slice(&(bucket_count as u32).to_le_bytes());
+            mmap[12..16].copy_from_slice(&(ENTRY_BYTES as u32).to_le_bytes());
+            for slot in mmap[MMAP_HEADER_BYTES..].chunks_exact_mut(ENTRY_BYTES) {
+                let mut buf = Vec::with_capacity(ENTRY_BYTES);
+                write_entry_bytes(&mut buf, &TTEntry::empty());
+                slot.copy_from_slice(&buf);
             }
         }
+
+        Ok(MmapTranspositionTable {
+            mmap,
+            mask: bucket_count - 1,
+            age: 1,
+        })
+    }
+
+    /// Byte offset of `key`'s slot within the mapping (header included).
+    #[inline]
+    fn index_of(&self, key: u64) -> usize {
+        let folded = key ^ (key >> 32) ^ (key >> 16);
+        MMAP_HEADER_BYTES + ((folded as usize) & self.mask) * ENTRY_BYTES
+    }
+
+    /// Called at the start of a fresh search iteration to age entries.
+    pub fn new_search(&mut self) {
+        self.age = self.age.wrapping_add(1);
+    }
+
+    /// Probe the mapped table for `key`. Same semantics as
+    /// `TranspositionTable::probe`, minus the cluster scan (one slot per
+    /// index here).
+    pub fn probe(&self, key: u64, depth: i32, alpha: i32, beta: i32, ply: i32) -> ProbeResult {
+        let offset = self.index_of(key);
+        let entry = read_entry_bytes(&self.mmap[offset..offset + ENTRY_BYTES]);
+        if entry.is_empty() || entry.key_check != key_check_of(key) {
+            return ProbeResult::Miss;
+        }
+        usable_result(entry, depth, alpha, beta, ply)
+    }
+
+    /// Store into the mapped table, overwriting whatever entry was
+    /// previously at `key`'s slot. `ply` is normalized into the stored
+    /// value exactly as in `TranspositionTable::store`.
+    pub fn store(
+        &mut self,
+        key: u64,
+        depth: i32,
+        value: i32,
+        node: NodeType,
+        best_move: Option<(usize, usize, u8)>,
+        ply: i32,
+    ) {
+        let offset = self.index_of(key);
+        let mut packed = PackedMove::none();
+        if let Some((from, to, promo_id)) = best_move {
+            packed = PackedMove::pack(from, to, promo_id);
+        }
+        let entry = TTEntry {
+            key_check: key_check_of(key),
+            value: to_tt_value(value, ply),
+            depth,
+            node,
+            age: self.age,
+            best: packed,
+        };
+        let mut buf = Vec::with_capacity(ENTRY_BYTES);
+        write_entry_bytes(&mut buf, &entry);
+        self.mmap[offset..offset + ENTRY_BYTES].copy_from_slice(&buf);
+    }
+
+    /// Flush the mapping to disk so the table survives the process exiting.
+    pub fn flush(&self) -> std::io::Result<()> {
+        self.mmap.flush()
     }
 }
 
@@ -440,14 +1126,15 @@ mod tests {
     fn simple_store_and_probe() {
         let mut tt = TranspositionTable::new_buckets(1024);
         tt.clear();
+        let mut zob = Zobrist::new();
         let key: u64 = 0x12345678abcdef;
         let depth = 5;
         let value = 42;
         let node = NodeType::Exact;
         tt.new_search();
-        tt.store(key, depth, value, node, Some((10usize, 20usize, 0u8)));
+        tt.store(key, depth, value, node, Some((10usize, 20usize, 0u8)), 0);
 
-        match tt.probe(key, depth, -1000000, 1000000) {
+        match tt.probe(key, depth, -1000000, 1000000, &mut zob, 0) {
             ProbeResult::Usable(v, Some(p)) => {
                 assert_eq!(v, 42);
                 let (f, t, p_id) = p.unpack();
@@ -457,6 +1144,7 @@ mod tests {
             }
             _ => panic!("expected usable exact"),
         }
+        assert_eq!(zob.stats().cluster_contention, 0);
     }
 
     #[test]
@@ -465,11 +1153,324 @@ mod tests {
         tt.clear();
         let key = 0x1111u64;
         tt.new_search();
-        tt.store(key, 3, 10, NodeType::UpperBound, None);
+        tt.store(key, 3, 10, NodeType::UpperBound, None, 0);
         let _first = tt.buckets[tt.index_of(key)];
-        tt.store(key, 6, 20, NodeType::Exact, None);
+        tt.store(key, 6, 20, NodeType::Exact, None, 0);
         let second = tt.buckets[tt.index_of(key)];
         assert_eq!(second.depth, 6);
         assert_eq!(second.value, 20);
     }
+
+    #[test]
+    fn probe_records_contention_on_key_check_mismatch() {
+        let mut tt = TranspositionTable::new_buckets(1);
+        tt.clear();
+        let mut zob = Zobrist::new();
+        tt.new_search();
+        // Both keys land in the single bucket (mask 0) but have different
+        // upper 16 bits, so the second probe must look like a miss with a
+        // recorded cluster contention rather than a (wrong) hit.
+        tt.store(0x0001_0000_0000_0000, 4, 1, NodeType::Exact, None, 0);
+        match tt.probe(0x0002_0000_0000_0000, 4, -1000000, 1000000, &mut zob, 0) {
+            ProbeResult::Miss => {}
+            other => panic!("expected miss on key_check mismatch, got {:?}", other),
+        }
+        assert_eq!(zob.stats().cluster_contention, 1);
+    }
+
+    #[test]
+    fn cluster_holds_multiple_colliding_keys_without_eviction() {
+        let mut tt = TranspositionTable::new_buckets(1);
+        tt.clear();
+        let mut zob = Zobrist::new();
+        tt.new_search();
+
+        // All CLUSTER_SIZE keys collide on the single cluster (mask 0) but
+        // carry distinct key_checks, so every one of them should survive.
+        let keys: Vec<u64> = (0..CLUSTER_SIZE as u64)
+            .map(|i| (i + 1) << 48)
+            .collect();
+        for (i, &key) in keys.iter().enumerate() {
+            tt.store(key, i as i32, i as i32 * 10, NodeType::Exact, None, 0);
+        }
+
+        for (i, &key) in keys.iter().enumerate() {
+            match tt.probe(key, 0, -1000000, 1000000, &mut zob, 0) {
+                ProbeResult::Usable(v, _) | ProbeResult::Found(TTEntry { value: v, .. }) => {
+                    assert_eq!(v, i as i32 * 10);
+                }
+                other => panic!("expected a hit for key {}, got {:?}", i, other),
+            }
+        }
+        assert_eq!(zob.stats().cluster_contention, 0);
+    }
+
+    #[test]
+    fn cluster_eviction_prefers_lowest_depth_age_score() {
+        let mut tt = TranspositionTable::new_buckets(1);
+        tt.clear();
+
+        // One entry from an older generation, then fill the rest of the
+        // cluster at the same depth in the current generation.
+        tt.new_search(); // age == 2
+        let stale_key = 1u64 << 48;
+        tt.store(stale_key, 10, 1, NodeType::Exact, None, 0);
+
+        tt.new_search(); // age == 3
+        let keys: Vec<u64> = (1..CLUSTER_SIZE as u64).map(|i| (i + 1) << 48).collect();
+        for &key in &keys {
+            tt.store(key, 10, 1, NodeType::Exact, None, 0);
+        }
+
+        // Cluster is now full. The stale entry is the only one carrying an
+        // age penalty, so it must be the one evicted even though every
+        // entry shares the same depth.
+        let new_key = (CLUSTER_SIZE as u64 + 1) << 48;
+        tt.store(new_key, 1, 99, NodeType::Exact, None, 0);
+
+        let base = tt.index_of(new_key);
+        let cluster = &tt.buckets[base..base + CLUSTER_SIZE];
+        assert!(
+            cluster
+                .iter()
+                .any(|e| !e.is_empty() && e.key_check == key_check_of(new_key)),
+            "new entry should have been stored"
+        );
+        assert!(
+            !cluster
+                .iter()
+                .any(|e| !e.is_empty() && e.key_check == key_check_of(stale_key)),
+            "stale entry should have been evicted"
+        );
+        for &key in &keys {
+            assert!(
+                cluster
+                    .iter()
+                    .any(|e| !e.is_empty() && e.key_check == key_check_of(key)),
+                "fresh same-generation entries should have survived"
+            );
+        }
+    }
+
+    #[test]
+    fn lockless_store_and_probe_round_trip() {
+        let tt = LocklessTranspositionTable::new_buckets(1024);
+        let key: u64 = 0xABCD_1234_5678_0000;
+        tt.store(key, 7, -55, NodeType::UpperBound, Some((12, 28, 0)), 0);
+
+        match tt.probe(key, 7, -50, 1000000, 0) {
+            ProbeResult::Usable(v, Some(p)) => {
+                assert_eq!(v, -55);
+                let (f, t, _) = p.unpack();
+                assert_eq!(f, 12);
+                assert_eq!(t, 28);
+            }
+            other => panic!("expected usable upper bound, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn lockless_probe_misses_on_untouched_slot() {
+        let tt = LocklessTranspositionTable::new_buckets(64);
+        match tt.probe(0x1234, 1, -1000000, 1000000, 0) {
+            ProbeResult::Miss => {}
+            other => panic!("expected miss on empty table, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn lockless_probe_detects_torn_write_as_miss() {
+        let tt = LocklessTranspositionTable::new_buckets(1);
+        let key = 0x1111_2222_3333_4444u64;
+        tt.store(key, 5, 10, NodeType::Exact, None, 0);
+
+        // Simulate a write torn by a concurrent writer: only the `data` atomic
+        // updated, not the XOR tag, so the two no longer agree on `key`.
+        let idx = tt.index_of(key);
+        let new_data = pack_lockless_data(&TTEntry {
+            key_check: 0,
+            value: 99,
+            depth: 1,
+            node: NodeType::Exact,
+            age: 1,
+            best: PackedMove::none(),
+        });
+        tt.slots[idx].data.store(new_data, Ordering::Relaxed);
+
+        match tt.probe(key, 1, -1000000, 1000000, 0) {
+            ProbeResult::Miss => {}
+            other => panic!("expected miss on torn write, got {:?}", other),
+        }
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "tt_compact_test_{}_{}_{:?}",
+            name,
+            std::process::id(),
+            std::thread::current().id()
+        ))
+    }
+
+    #[test]
+    fn save_load_compact_sparse_round_trip() {
+        let mut zob = Zobrist::new();
+        let mut tt = TranspositionTable::new_buckets(8);
+        let keys = [0xAAAA_1111u64, 0xBBBB_2222u64, 0xCCCC_3333u64];
+        for (i, &key) in keys.iter().enumerate() {
+            tt.store(key, i as i32 + 3, 100 + i as i32, NodeType::Exact, None, 0);
+        }
+
+        let path = temp_path("sparse");
+        tt.save_to_file_compact(&path, true, false).unwrap();
+
+        let mut loaded = TranspositionTable::new_buckets(8);
+        loaded.load_from_file_compact(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        for (i, &key) in keys.iter().enumerate() {
+            match loaded.probe(key, i as i32 + 3, -1000000, 1000000, &mut zob, 0) {
+                ProbeResult::Usable(value, _) => assert_eq!(value, 100 + i as i32),
+                other => panic!("expected usable entry, got {:?}", other),
+            }
+        }
+        assert_eq!(loaded.buckets.iter().filter(|e| !e.is_empty()).count(), 3);
+    }
+
+    #[test]
+    fn save_load_compact_dense_compressed_round_trip() {
+        let mut zob = Zobrist::new();
+        let mut tt = TranspositionTable::new_buckets(4);
+        let key = 0xDEAD_BEEFu64;
+        tt.store(key, 9, -42, NodeType::LowerBound, Some((12, 28, 0)), 0);
+
+        let path = temp_path("dense_compressed");
+        tt.save_to_file_compact(&path, false, true).unwrap();
+
+        let mut loaded = TranspositionTable::new_buckets(4);
+        loaded.load_from_file_compact(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        match loaded.probe(key, 9, -1000000, -100, &mut zob, 0) {
+            ProbeResult::Usable(value, mv) => {
+                assert_eq!(value, -42);
+                assert_eq!(mv.unwrap().unpack(), (12, 28, 0));
+            }
+            other => panic!("expected usable entry, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn load_compact_rejects_legacy_dense_dump() {
+        let tt = TranspositionTable::new_buckets(4);
+        let path = temp_path("legacy_magic");
+        tt.save_to_file(&path).unwrap();
+
+        let mut loaded = TranspositionTable::new_buckets(4);
+        let err = loaded.load_from_file_compact(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn mmap_store_and_probe_round_trip() {
+        let path = temp_path("mmap_round_trip");
+        let mut tt = MmapTranspositionTable::open_mmap(&path, 1).unwrap();
+        let key = 0x1234_5678_9ABC_DEF0u64;
+        tt.store(key, 6, 77, NodeType::Exact, Some((4, 20, 0)), 0);
+
+        match tt.probe(key, 6, -1000000, 1000000, 0) {
+            ProbeResult::Usable(value, mv) => {
+                assert_eq!(value, 77);
+                assert_eq!(mv.unwrap().unpack(), (4, 20, 0));
+            }
+            other => panic!("expected usable entry, got {:?}", other),
+        }
+        drop(tt);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn mmap_reopen_with_matching_size_preserves_entries() {
+        let path = temp_path("mmap_reopen");
+        let key = 0xFEED_FACE_0000_1111u64;
+        {
+            let mut tt = MmapTranspositionTable::open_mmap(&path, 1).unwrap();
+            tt.store(key, 5, -13, NodeType::Exact, None, 0);
+            tt.flush().unwrap();
+        }
+
+        let tt = MmapTranspositionTable::open_mmap(&path, 1).unwrap();
+        match tt.probe(key, 5, -1000000, 1000000, 0) {
+            ProbeResult::Usable(value, _) => assert_eq!(value, -13),
+            other => panic!("expected entry to survive reopen, got {:?}", other),
+        }
+        drop(tt);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn mmap_probe_misses_on_untouched_slot() {
+        let path = temp_path("mmap_miss");
+        let tt = MmapTranspositionTable::open_mmap(&path, 1).unwrap();
+        match tt.probe(0x9999_8888_7777_6666u64, 1, -1000000, 1000000, 0) {
+            ProbeResult::Miss => {}
+            other => panic!("expected miss, got {:?}", other),
+        }
+        drop(tt);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn prefetch_does_not_affect_probe_result() {
+        let mut zob = Zobrist::new();
+        let mut tt = TranspositionTable::new_buckets(4);
+        let lockless = LocklessTranspositionTable::new_buckets(4);
+        let key = 0x0102_0304_0506_0708u64;
+
+        tt.prefetch(key);
+        lockless.prefetch(key);
+        assert!(matches!(
+            tt.probe(key, 1, -1000000, 1000000, &mut zob, 0),
+            ProbeResult::Miss
+        ));
+        assert!(matches!(
+            lockless.probe(key, 1, -1000000, 1000000, 0),
+            ProbeResult::Miss
+        ));
+
+        tt.store(key, 3, 1, NodeType::Exact, None, 0);
+        tt.prefetch(key);
+        assert!(matches!(
+            tt.probe(key, 3, -1000000, 1000000, &mut zob, 0),
+            ProbeResult::Usable(1, _)
+        ));
+    }
+
+    #[test]
+    fn mate_score_adjusted_for_ply_on_store_and_probe() {
+        let mut zob = Zobrist::new();
+        let mut tt = TranspositionTable::new_buckets(4);
+        let key = 0x2468_ACE0_1357_9BDFu64;
+
+        // A mate 2 plies below a node searched at ply 5 scores as mate in 7
+        // from the root; it is stored node-relative (mate in 2), so the same
+        // position reached at a different ply still reports the right
+        // distance relative to its new root.
+        let mate_in_7_from_root = MATE - 7;
+        tt.store(key, 4, mate_in_7_from_root, NodeType::Exact, None, 5);
+
+        match tt.probe(key, 4, -MATE, MATE, &mut zob, 5) {
+            ProbeResult::Usable(value, _) => assert_eq!(value, mate_in_7_from_root),
+            other => panic!("expected usable mate score, got {:?}", other),
+        }
+
+        // Reached again at a shallower ply (1 instead of 5), the same
+        // node-relative distance now reports as a *closer* mate (in 3) from
+        // the root - exactly the distance correction this is for.
+        match tt.probe(key, 4, -MATE, MATE, &mut zob, 1) {
+            ProbeResult::Usable(value, _) => assert_eq!(value, MATE - 3),
+            other => panic!("expected usable mate score, got {:?}", other),
+        }
+    }
 }
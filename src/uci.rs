@@ -0,0 +1,185 @@
+// Universal Chess Interface (UCI) mode: lets external GUIs and tournament
+// managers drive the engine over stdin/stdout instead of the bundled
+// terminal UI. See https://www.chessprogramming.org/UCI for the protocol.
+
+use crate::engine::{analyze_uci, Board, Piece, Sq};
+use std::io::{self, Write};
+
+const ENGINE_NAME: &str = "rust_chess_engine";
+const ENGINE_AUTHOR: &str = "JANARTHANAN6363";
+
+fn alg_to_sq(s: &str) -> Option<Sq> {
+    let s = s.trim();
+    if s.len() < 2 {
+        return None;
+    }
+    let bytes = s.as_bytes();
+    let f = (bytes[0] as char).to_ascii_lowercase();
+    let rch = bytes[1] as char;
+    if !('a'..='h').contains(&f) || !('1'..='8').contains(&rch) {
+        return None;
+    }
+    let file = (f as u8 - b'a') as usize;
+    let rank = (rch as u8 - b'1') as usize;
+    Some((rank << 4) | file)
+}
+
+fn apply_lan_move(board: &mut Board, lan: &str) -> bool {
+    if lan.len() < 4 {
+        return false;
+    }
+    let (from, to) = match (alg_to_sq(&lan[0..2]), alg_to_sq(&lan[2..4])) {
+        (Some(f), Some(t)) => (f, t),
+        _ => return false,
+    };
+    let promotion = if lan.len() >= 5 {
+        Some(Piece::from_char(lan.chars().nth(4).unwrap()))
+    } else {
+        None
+    };
+    board.make_move(from, to, promotion);
+    true
+}
+
+/// Rebuild a `Board` from a `position [startpos|fen <fen>] moves <m1> <m2> ...` command.
+fn handle_position(tokens: &[&str]) -> Board {
+    let mut board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    let mut idx = 0;
+
+    if idx < tokens.len() && tokens[idx] == "startpos" {
+        idx += 1;
+    } else if idx < tokens.len() && tokens[idx] == "fen" {
+        idx += 1;
+        let fen_start = idx;
+        while idx < tokens.len() && tokens[idx] != "moves" {
+            idx += 1;
+        }
+        let fen = tokens[fen_start..idx].join(" ");
+        board = Board::from_fen(&fen);
+    }
+
+    if idx < tokens.len() && tokens[idx] == "moves" {
+        idx += 1;
+        for mv in &tokens[idx..] {
+            apply_lan_move(&mut board, mv);
+        }
+    }
+
+    board
+}
+
+/// Search parameters parsed out of a `go` command.
+struct GoOptions {
+    depth: Option<i32>,
+    movetime: Option<u64>,
+    wtime: Option<u64>,
+    btime: Option<u64>,
+}
+
+fn parse_go(tokens: &[&str]) -> GoOptions {
+    let mut opts = GoOptions {
+        depth: None,
+        movetime: None,
+        wtime: None,
+        btime: None,
+    };
+    let mut i = 0;
+    while i < tokens.len() {
+        match tokens[i] {
+            "depth" => {
+                if let Some(v) = tokens.get(i + 1).and_then(|s| s.parse::<i32>().ok()) {
+                    opts.depth = Some(v);
+                }
+                i += 2;
+            }
+            "movetime" => {
+                if let Some(v) = tokens.get(i + 1).and_then(|s| s.parse::<u64>().ok()) {
+                    opts.movetime = Some(v);
+                }
+                i += 2;
+            }
+            "wtime" => {
+                if let Some(v) = tokens.get(i + 1).and_then(|s| s.parse::<u64>().ok()) {
+                    opts.wtime = Some(v);
+                }
+                i += 2;
+            }
+            "btime" => {
+                if let Some(v) = tokens.get(i + 1).and_then(|s| s.parse::<u64>().ok()) {
+                    opts.btime = Some(v);
+                }
+                i += 2;
+            }
+            _ => i += 1,
+        }
+    }
+    opts
+}
+
+/// Turn parsed `go` options plus the side to move into a (depth, time budget)
+/// pair suitable for `ai_move`.
+fn search_budget(opts: &GoOptions, side_white: bool) -> (i32, Option<u64>) {
+    if let Some(d) = opts.depth {
+        return (d, opts.movetime);
+    }
+    if let Some(ms) = opts.movetime {
+        return (64, Some(ms));
+    }
+    let remaining = if side_white { opts.wtime } else { opts.btime };
+    if let Some(ms) = remaining {
+        return (64, Some(ms / 30));
+    }
+    (6, None)
+}
+
+/// Run the UCI command loop against stdin/stdout until `quit` or EOF.
+pub fn run() {
+    let stdin = io::stdin();
+    let mut board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+    loop {
+        let mut line = String::new();
+        if stdin.read_line(&mut line).is_err() || line.is_empty() {
+            break;
+        }
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.is_empty() {
+            continue;
+        }
+
+        match tokens[0] {
+            "uci" => {
+                println!("id name {}", ENGINE_NAME);
+                println!("id author {}", ENGINE_AUTHOR);
+                println!("uciok");
+                io::stdout().flush().ok();
+            }
+            "isready" => {
+                println!("readyok");
+                io::stdout().flush().ok();
+            }
+            "ucinewgame" => {
+                board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+            }
+            "position" => {
+                board = handle_position(&tokens[1..]);
+            }
+            "go" => {
+                let opts = parse_go(&tokens[1..]);
+                let (depth, time_ms) = search_budget(&opts, board.side_white);
+                let result = analyze_uci(&mut board, depth, time_ms);
+                match result.best_move {
+                    Some(mv) => println!("bestmove {}", mv),
+                    None => println!("bestmove 0000"),
+                }
+                io::stdout().flush().ok();
+            }
+            // The search is synchronous and already respects `go`'s own time
+            // budget, so there's nothing to interrupt mid-search; `stop` just
+            // needs to be accepted rather than falling through as unknown.
+            "stop" => {}
+            "quit" => break,
+            _ => {}
+        }
+    }
+}
@@ -1,14 +1,27 @@
 // Integration layer between terminal UI and chess engine
 
-use crate::engine::{Board, Move, Piece, Sq, ai_move, gen_moves, is_king_attacked};
+use crate::engine::{
+    Board, Move, Piece, Sq, ai_move, analyze, eval, gen_moves, is_king_attacked, material_score,
+    perft,
+};
+use crate::tactics::TacticsTrainer;
 use crate::ui::{
-    AsciiArt, ConfirmDialog, GameInterface, GameMode, GameResult, GameSettings, InputValidator,
-    MoveHistoryDisplay, Notification, NotificationKind, StatsDisplay, create_game_mode_menu,
-    create_main_menu,
+    AsciiArt, Clock, ConfirmDialog, GameInterface, GameMode, GameResult, GameSettings,
+    InputValidator, MoveHistoryDisplay, Notification, NotificationKind, Prompt, StatsDisplay,
+    Table, create_game_mode_menu, create_main_menu,
 };
-use std::io::{self, Write};
+use std::io;
 use std::time::Instant;
 
+/// Commands recognized at the interactive move/analysis prompts, offered as
+/// completions by `Prompt`.
+const ANALYSIS_COMMANDS: &[&str] = &[
+    "move", "undo", "redo", "hint", "analyze", "perft", "flip", "resign", "menu", "help",
+];
+
+/// Where tactics-trainer spaced-repetition progress is persisted between runs.
+const TACTICS_PROGRESS_FILE: &str = "tactics_progress.dat";
+
 // ============================================================================
 // GAME CONTROLLER
 // ============================================================================
@@ -19,6 +32,8 @@ pub struct GameController {
     settings: GameSettings,
     move_history: MoveHistoryDisplay,
     game_active: bool,
+    game_result: Option<GameResult>,
+    clock: Option<Clock>,
 }
 
 impl GameController {
@@ -29,6 +44,8 @@ impl GameController {
             settings: GameSettings::default(),
             move_history: MoveHistoryDisplay::new(),
             game_active: false,
+            game_result: None,
+            clock: None,
         }
     }
 
@@ -90,6 +107,12 @@ impl GameController {
         self.move_history.clear();
         self.interface.clear_history();
         self.game_active = true;
+        self.game_result = None;
+        self.clock = if mode == GameMode::Analysis {
+            None
+        } else {
+            Clock::from_time_control(self.settings.time_control)
+        };
 
         Notification::new(
             "Game started! Good luck!".to_string(),
@@ -113,34 +136,43 @@ impl GameController {
             // Check for game end
             if let Some(result) = self.check_game_end() {
                 self.interface.show_game_result(result);
+                self.game_result = Some(result);
                 self.game_active = false;
                 break;
             }
 
+            self.tick_clock();
+
             if self.board.side_white {
                 // Human move (white)
                 if !self.handle_human_move() {
                     break; // User quit
                 }
+                if self.charge_clock(true) {
+                    break;
+                }
             } else {
                 // Engine move (black)
                 Notification::new("Engine is thinking...".to_string(), NotificationKind::Info)
                     .show();
 
-                let depth = self.settings.get_search_depth();
+                let (depth, time_ms) = self.engine_search_budget();
                 let start = Instant::now();
 
-                if let Some(mv) = ai_move(&mut self.board, depth, Some(5000)) {
+                if let Some(mv) = ai_move(&mut self.board, depth, Some(time_ms)) {
                     let elapsed = start.elapsed().as_millis();
 
-                    let move_str =
-                        format!("{}{}", Self::sq_to_alg(mv.from), Self::sq_to_alg(mv.to));
+                    let move_str = Self::format_move(&mv);
 
                     self.board.make_move(mv.from, mv.to, mv.promotion);
                     self.interface.highlight_move(mv.from, mv.to);
                     self.move_history.add_move(move_str.clone());
                     self.interface.add_move_to_history(move_str);
 
+                    if self.charge_clock(false) {
+                        break;
+                    }
+
                     Notification::new(
                         format!("Engine played {} ({}ms)", Self::format_move(&mv), elapsed),
                         NotificationKind::Success,
@@ -162,13 +194,20 @@ impl GameController {
 
             if let Some(result) = self.check_game_end() {
                 self.interface.show_game_result(result);
+                self.game_result = Some(result);
                 self.game_active = false;
                 break;
             }
 
+            self.tick_clock();
+            let white_to_move = self.board.side_white;
+
             if !self.handle_human_move() {
                 break;
             }
+            if self.charge_clock(white_to_move) {
+                break;
+            }
         }
 
         self.show_end_game_options();
@@ -186,20 +225,28 @@ impl GameController {
 
             if let Some(result) = self.check_game_end() {
                 self.interface.show_game_result(result);
+                self.game_result = Some(result);
                 self.game_active = false;
                 break;
             }
 
+            self.tick_clock();
+            let white_to_move = self.board.side_white;
+
             // Both sides use engine
-            let depth = 5;
-            if let Some(mv) = ai_move(&mut self.board, depth, Some(3000)) {
-                let move_str = format!("{}{}", Self::sq_to_alg(mv.from), Self::sq_to_alg(mv.to));
+            let (depth, time_ms) = self.engine_search_budget();
+            if let Some(mv) = ai_move(&mut self.board, depth, Some(time_ms)) {
+                let move_str = Self::format_move(&mv);
 
                 self.board.make_move(mv.from, mv.to, mv.promotion);
                 self.interface.highlight_move(mv.from, mv.to);
                 self.move_history.add_move(move_str.clone());
                 self.interface.add_move_to_history(move_str);
 
+                if self.charge_clock(white_to_move) {
+                    break;
+                }
+
                 std::thread::sleep(std::time::Duration::from_millis(500));
             } else {
                 break;
@@ -209,17 +256,57 @@ impl GameController {
         self.show_end_game_options();
     }
 
+    /// Start timing the side to move and redraw the live clock face. A
+    /// no-op when the game has no clock (`TimeControl::Unlimited`).
+    fn tick_clock(&mut self) {
+        let white_to_move = self.board.side_white;
+        if let Some(clock) = self.clock.as_mut() {
+            clock.start_turn();
+            clock.render(white_to_move);
+        }
+    }
+
+    /// Stop timing the side that just moved. Returns `true` if this flagged
+    /// them, in which case a flag-fall notification ends the game.
+    fn charge_clock(&mut self, white_moved: bool) -> bool {
+        let Some(clock) = self.clock.as_mut() else {
+            return false;
+        };
+        if !clock.end_turn(white_moved) {
+            return false;
+        }
+
+        let loser = if white_moved { "White" } else { "Black" };
+        Notification::new(
+            format!("Flag fell! {} loses on time.", loser),
+            NotificationKind::Error,
+        )
+        .show();
+        self.game_result = Some(if white_moved {
+            GameResult::BlackWins
+        } else {
+            GameResult::WhiteWins
+        });
+        self.game_active = false;
+        true
+    }
+
+    /// Search depth and time budget for an engine move. With a clock
+    /// running, the depth cap is generous and the clock's per-move time
+    /// allocation drives the cutoff instead of `get_search_depth`.
+    fn engine_search_budget(&self) -> (i32, u64) {
+        match &self.clock {
+            Some(clock) => (32, clock.allocate_move_time(self.board.side_white)),
+            None => (self.settings.get_search_depth(), 5000),
+        }
+    }
+
     fn analysis_mode(&mut self) {
         loop {
             self.interface.show_game_screen(&self.board);
 
             println!("\nAnalysis Mode - Enter command:");
-            print!("> ");
-            io::stdout().flush().unwrap();
-
-            let mut input = String::new();
-            io::stdin().read_line(&mut input).unwrap();
-            let input = input.trim();
+            let input = self.prompt_move_or_command("");
 
             if input.is_empty() {
                 continue;
@@ -244,6 +331,14 @@ impl GameController {
                 "eval" | "e" => {
                     self.show_evaluation();
                 }
+                "perft" | "divide" => {
+                    let depth = input
+                        .split_whitespace()
+                        .nth(1)
+                        .and_then(|s| s.parse::<u32>().ok())
+                        .unwrap_or(4);
+                    self.run_perft(depth);
+                }
                 "undo" | "u" => {
                     self.board.undo_move();
                     self.interface.show_success("Move undone");
@@ -265,6 +360,20 @@ impl GameController {
         }
     }
 
+    /// Read a move/command line through a [`Prompt`], completing against
+    /// `ANALYSIS_COMMANDS` at the first token and against the engine's
+    /// current legal moves once the first token is `move`. The legal-move
+    /// closure is the only place this module touches move generation for
+    /// completion purposes, keeping `Prompt` itself decoupled from it.
+    fn prompt_move_or_command(&self, label: &str) -> String {
+        let prompt = Prompt::new(ANALYSIS_COMMANDS);
+        prompt.read_line(label, || {
+            let mut moves = Vec::new();
+            gen_moves(&self.board, &mut moves);
+            moves.iter().map(Self::format_move).collect()
+        })
+    }
+
     fn handle_human_move(&mut self) -> bool {
         loop {
             let side = if self.board.side_white {
@@ -273,7 +382,7 @@ impl GameController {
                 "Black"
             };
             let prompt = format!("{} to move", side);
-            let input = self.interface.prompt_input(&prompt);
+            let input = self.prompt_move_or_command(&prompt);
 
             if input.is_empty() {
                 continue;
@@ -291,8 +400,7 @@ impl GameController {
 
                     match self.try_make_move(parts[1]) {
                         Ok(mv) => {
-                            let move_str =
-                                format!("{}{}", Self::sq_to_alg(mv.from), Self::sq_to_alg(mv.to));
+                            let move_str = Self::format_move(&mv);
 
                             self.interface.highlight_move(mv.from, mv.to);
                             self.move_history.add_move(move_str.clone());
@@ -329,6 +437,11 @@ impl GameController {
                 }
                 "resign" => {
                     if ConfirmDialog::confirm("Are you sure you want to resign?") {
+                        self.game_result = Some(if self.board.side_white {
+                            GameResult::BlackWins
+                        } else {
+                            GameResult::WhiteWins
+                        });
                         self.game_active = false;
                         return false;
                     }
@@ -349,8 +462,7 @@ impl GameController {
                     // Try to parse as move directly
                     match self.try_make_move(parts[0]) {
                         Ok(mv) => {
-                            let move_str =
-                                format!("{}{}", Self::sq_to_alg(mv.from), Self::sq_to_alg(mv.to));
+                            let move_str = Self::format_move(&mv);
 
                             self.interface.highlight_move(mv.from, mv.to);
                             self.move_history.add_move(move_str.clone());
@@ -371,6 +483,14 @@ impl GameController {
     }
 
     fn try_make_move(&mut self, move_str: &str) -> Result<Move, String> {
+        Self::apply_move(&mut self.board, move_str)
+    }
+
+    /// Validate `move_str` as LAN, check it's legal in `board`, and play it.
+    /// Takes the board as a parameter (rather than using `self.board`
+    /// directly) so the tactics trainer can verify puzzle attempts against a
+    /// scratch board without disturbing the active game.
+    fn apply_move(board: &mut Board, move_str: &str) -> Result<Move, String> {
         let (from_str, to_str, promo_char) = InputValidator::validate_move(move_str)?;
 
         let from = Self::alg_to_sq(&from_str)
@@ -382,7 +502,7 @@ impl GameController {
 
         // Verify move is legal
         let mut legal_moves = Vec::new();
-        gen_moves(&self.board, &mut legal_moves);
+        gen_moves(board, &mut legal_moves);
 
         let is_legal = legal_moves
             .iter()
@@ -392,7 +512,7 @@ impl GameController {
             return Err("Illegal move!".to_string());
         }
 
-        self.board.make_move(from, to, promotion);
+        board.make_move(from, to, promotion);
 
         Ok(Move {
             from,
@@ -409,7 +529,8 @@ impl GameController {
         .show();
 
         let depth = self.settings.get_search_depth();
-        if let Some(mv) = ai_move(&mut self.board, depth, Some(3000)) {
+        let analysis = analyze(&mut self.board, depth, Some(3000));
+        if let Some(mv) = analysis.best_move {
             let hint = format!("Suggested move: {}", Self::format_move(&mv));
             Notification::new(hint, NotificationKind::Success).show();
         } else {
@@ -424,43 +545,89 @@ impl GameController {
         println!("{}{}Running deep analysis...{}", BOLD, BRIGHT_CYAN, RESET);
 
         let start = Instant::now();
-        if let Some(mv) = ai_move(&mut self.board, depth, None) {
+        let analysis = analyze(&mut self.board, depth, None);
+        if let Some(mv) = analysis.best_move {
             let elapsed = start.elapsed().as_millis();
+            let pv = Self::format_pv(&analysis.pv);
 
             self.interface.display.print_analysis(
                 depth,
-                0, // Would need to extract score from search
-                0, // Would need to track nodes
+                analysis.score,
+                analysis.nodes,
                 elapsed,
-                &Self::format_move(&mv),
+                &pv.unwrap_or_else(|| Self::format_move(&mv)),
             );
         }
     }
 
-    fn show_evaluation(&self) {
-        // Simple material evaluation for now
-        let mut score = 0;
-        for sq in 0..128 {
-            if (sq & 0x88) != 0 {
-                continue;
-            }
-            let piece = self.board.cells[sq];
-            score += match piece {
-                Piece::WP => 100,
-                Piece::WN => 320,
-                Piece::WB => 330,
-                Piece::WR => 500,
-                Piece::WQ => 900,
-                Piece::BP => -100,
-                Piece::BN => -320,
-                Piece::BB => -330,
-                Piece::BR => -500,
-                Piece::BQ => -900,
-                _ => 0,
-            };
+    /// Run perft to `depth` from the current position and render a
+    /// per-root-move node count ("divide") plus the grand total through the
+    /// `Table` widget, reusing `StatsDisplay`'s nodes/second computation.
+    fn run_perft(&mut self, depth: u32) {
+        use crate::ui::colors::*;
+
+        println!();
+        println!(
+            "{}{}Running perft({})...{}",
+            BOLD, BRIGHT_CYAN, depth, RESET
+        );
+
+        let start = Instant::now();
+        let mut moves = Vec::new();
+        gen_moves(&self.board, &mut moves);
+
+        let mut table = Table::new(vec!["Move".to_string(), "Nodes".to_string()]);
+        let mut total = 0u64;
+        for mv in &moves {
+            self.board.make_move(mv.from, mv.to, mv.promotion);
+            let nodes = perft(&mut self.board, depth.saturating_sub(1));
+            self.board.undo_move();
+            table.add_row(vec![Self::format_move(mv), nodes.to_string()]);
+            total += nodes;
+        }
+        let elapsed = start.elapsed().as_millis();
+
+        table.render();
+        println!();
+        println!("Total nodes:   {}", total);
+        println!("Nodes/Second:  {}", StatsDisplay::nodes_per_second(total, elapsed));
+        println!("Elapsed:       {}ms", elapsed);
+        println!();
+    }
+
+    /// Render a principal variation as a space-separated LAN sequence.
+    fn format_pv(pv: &[Move]) -> Option<String> {
+        if pv.is_empty() {
+            return None;
+        }
+        Some(
+            pv.iter()
+                .map(Self::format_move)
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    }
+
+    fn show_evaluation(&mut self) {
+        let material = material_score(&self.board);
+        let total = if self.board.side_white {
+            eval(&self.board)
+        } else {
+            -eval(&self.board)
+        };
+        let positional = total - material;
+
+        let analysis = analyze(&mut self.board, 4, Some(1500));
+        if analysis.score.abs() > 20000 {
+            let mate_in = analysis.pv.len().div_ceil(2);
+            Notification::new(
+                format!("Forced mate detected: #{}", mate_in),
+                NotificationKind::Success,
+            )
+            .show();
         }
 
-        StatsDisplay::show_position_eval(score, 0, score);
+        StatsDisplay::show_position_eval(material, positional, total);
     }
 
     fn check_game_end(&self) -> Option<GameResult> {
@@ -487,9 +654,67 @@ impl GameController {
             return Some(GameResult::Draw);
         }
 
+        if self.is_threefold_repetition() {
+            return Some(GameResult::Draw);
+        }
+
+        if Self::is_insufficient_material(&self.board) {
+            return Some(GameResult::Draw);
+        }
+
         None
     }
 
+    /// `self.board` already tracks every visited position's hash
+    /// incrementally in `position_history`, which is exactly what
+    /// `Board::is_draw` counts repetitions from - so there's no need to
+    /// replay the whole game from scratch with a fresh `Zobrist` instance
+    /// just to recount them here.
+    fn is_threefold_repetition(&self) -> bool {
+        self.board.is_draw()
+    }
+
+    /// Detect the standard forced draws: K vs K, K+minor vs K, and K+B vs K+B
+    /// with same-colored bishops, with no pawns, rooks, or queens on the board.
+    fn is_insufficient_material(board: &Board) -> bool {
+        let mut white_minors = Vec::new();
+        let mut black_minors = Vec::new();
+
+        for r in 0..8usize {
+            for f in 0..8usize {
+                let s = (r << 4) | f;
+                match board.cells[s] {
+                    Piece::Empty | Piece::WK | Piece::BK => {}
+                    Piece::WP | Piece::BP | Piece::WR | Piece::BR | Piece::WQ | Piece::BQ => {
+                        return false;
+                    }
+                    Piece::WN | Piece::WB => white_minors.push((s, board.cells[s])),
+                    Piece::BN | Piece::BB => black_minors.push((s, board.cells[s])),
+                }
+            }
+        }
+
+        match (white_minors.len(), black_minors.len()) {
+            (0, 0) => true,
+            (1, 0) | (0, 1) => true,
+            (1, 1) => {
+                let (ws, wp) = white_minors[0];
+                let (bs, bp) = black_minors[0];
+                if wp == Piece::WB && bp == Piece::BB {
+                    Self::square_color(ws) == Self::square_color(bs)
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Light or dark square, from 0x88 coordinates, for same-bishop-color checks.
+    fn square_color(s: Sq) -> bool {
+        ((s >> 4) + (s & 15)).is_multiple_of(2)
+    }
+
     fn show_end_game_options(&self) {
         let options = vec!["New Game", "Analyze Game", "Save Game", "Main Menu"];
 
@@ -512,9 +737,81 @@ impl GameController {
             return;
         }
 
-        // TODO: Implement PGN saving
-        self.interface
-            .show_success(&format!("Game saved to {}", filename));
+        let mut replay = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let mut sans = Vec::new();
+        for coord in self.move_history.moves() {
+            match Self::coord_to_move(&replay, coord) {
+                Some(mv) => sans.push(crate::pgn::san_for_move(&mut replay, mv)),
+                None => {
+                    self.interface
+                        .show_error(&format!("Could not replay recorded move '{}'", coord));
+                    return;
+                }
+            }
+        }
+
+        let result = crate::pgn::result_token(self.game_result);
+        let pgn = crate::pgn::format_pgn("?", "?", "1", result, &sans);
+
+        match std::fs::write(&filename, pgn) {
+            Ok(_) => self
+                .interface
+                .show_success(&format!("Game saved to {}", filename)),
+            Err(e) => self
+                .interface
+                .show_error(&format!("Failed to save game: {}", e)),
+        }
+    }
+
+    /// Parse a stored coordinate move (e.g. "e2e4", "e7e8q") into a `Move`,
+    /// inferring the promotion piece's color from the side to move.
+    fn coord_to_move(board: &Board, coord: &str) -> Option<Move> {
+        if coord.len() < 4 {
+            return None;
+        }
+        let from = Self::alg_to_sq(&coord[0..2])?;
+        let to = Self::alg_to_sq(&coord[2..4])?;
+        let promotion = if coord.len() >= 5 {
+            let white = board.side_white;
+            Some(match coord.chars().nth(4)?.to_ascii_lowercase() {
+                'q' => {
+                    if white {
+                        Piece::WQ
+                    } else {
+                        Piece::BQ
+                    }
+                }
+                'r' => {
+                    if white {
+                        Piece::WR
+                    } else {
+                        Piece::BR
+                    }
+                }
+                'b' => {
+                    if white {
+                        Piece::WB
+                    } else {
+                        Piece::BB
+                    }
+                }
+                'n' => {
+                    if white {
+                        Piece::WN
+                    } else {
+                        Piece::BN
+                    }
+                }
+                _ => return None,
+            })
+        } else {
+            None
+        };
+        Some(Move {
+            from,
+            to,
+            promotion,
+        })
     }
 
     fn load_game(&mut self) {
@@ -525,8 +822,53 @@ impl GameController {
             return;
         }
 
-        // TODO: Implement PGN loading
-        self.interface.show_error("Load game not yet implemented");
+        let text = match std::fs::read_to_string(&filename) {
+            Ok(t) => t,
+            Err(e) => {
+                self.interface
+                    .show_error(&format!("Failed to read '{}': {}", filename, e));
+                return;
+            }
+        };
+
+        let tags = crate::pgn::parse_tags(&text);
+        let start_fen = tags
+            .iter()
+            .find(|(k, _)| k == "FEN")
+            .map(|(_, v)| v.as_str())
+            .unwrap_or("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let mut board = Board::from_fen(start_fen);
+
+        let (sans, result_tag) = crate::pgn::parse_movetext(&text);
+        let mut coords = Vec::new();
+        for san in &sans {
+            match crate::pgn::parse_san(&board, san) {
+                Ok(mv) => {
+                    coords.push(Self::format_move(&mv));
+                    board.make_move(mv.from, mv.to, mv.promotion);
+                }
+                Err(e) => {
+                    self.interface
+                        .show_error(&format!("Failed to load game: {}", e));
+                    return;
+                }
+            }
+        }
+
+        self.board = board;
+        self.move_history.clear();
+        self.interface.clear_history();
+        for coord in &coords {
+            self.move_history.add_move(coord.clone());
+            self.interface.add_move_to_history(coord.clone());
+        }
+        self.game_result = result_tag.as_deref().and_then(crate::pgn::result_from_token);
+        self.game_active = true;
+        self.interface.set_game_mode(GameMode::Analysis);
+
+        self.interface
+            .show_success(&format!("Loaded {} moves from {}", coords.len(), filename));
+        self.analysis_mode();
     }
 
     fn configure_settings(&mut self) {
@@ -534,8 +876,97 @@ impl GameController {
         Notification::new("Settings updated!".to_string(), NotificationKind::Success).show();
     }
 
-    fn show_tutorial(&self) {
-        self.interface.show_help();
+    fn show_tutorial(&mut self) {
+        let options = vec!["How to Play", "Tactics Trainer", "Back"];
+        match ConfirmDialog::choose("Tutorial", &options) {
+            0 => self.interface.show_help(),
+            1 => self.run_tactics_trainer(),
+            _ => {}
+        }
+    }
+
+    /// Spaced-repetition tactics drill: work through the cards currently due
+    /// via `TacticsTrainer`'s SM-2 schedule, grading each attempt and saving
+    /// progress back to disk after every card.
+    fn run_tactics_trainer(&mut self) {
+        let mut trainer = TacticsTrainer::load(TACTICS_PROGRESS_FILE);
+
+        loop {
+            let now = TacticsTrainer::now_unix();
+            let due = trainer.due_indices(now);
+
+            let Some(&index) = due.first() else {
+                Notification::new(
+                    "No tactics due right now - come back later!".to_string(),
+                    NotificationKind::Info,
+                )
+                .show();
+                break;
+            };
+
+            let fen = trainer.cards()[index].fen.clone();
+            let solution = trainer.cards()[index].solution.clone();
+            let mut puzzle_board = Board::from_fen(&fen);
+
+            self.interface.display.render(&puzzle_board);
+            println!(
+                "Find the best move for {}. Type a move, or 'quit' to stop.",
+                if puzzle_board.side_white {
+                    "White"
+                } else {
+                    "Black"
+                }
+            );
+
+            let prompt = Prompt::default();
+            let answer = prompt.read_line("solution", || {
+                let mut moves = Vec::new();
+                gen_moves(&puzzle_board, &mut moves);
+                moves.iter().map(Self::format_move).collect()
+            });
+
+            if answer.eq_ignore_ascii_case("quit") {
+                break;
+            }
+
+            let correct = Self::apply_move(&mut puzzle_board, &answer)
+                .map(|mv| Self::format_move(&mv).eq_ignore_ascii_case(&solution))
+                .unwrap_or(false);
+
+            trainer.grade(index, if correct { 5 } else { 2 }, now);
+
+            if correct {
+                Notification::new("Correct!".to_string(), NotificationKind::Success).show();
+            } else {
+                Notification::new(
+                    format!("Not quite - the move was {}", solution),
+                    NotificationKind::Error,
+                )
+                .show();
+            }
+
+            let card = &trainer.cards()[index];
+            let mut summary = Table::new(vec!["Metric".to_string(), "Value".to_string()]);
+            summary.add_row(vec![
+                "Ease Factor".to_string(),
+                format!("{:.2}", card.ease_factor),
+            ]);
+            summary.add_row(vec!["Repetitions".to_string(), card.repetitions.to_string()]);
+            summary.add_row(vec![
+                "Next Interval (days)".to_string(),
+                card.interval_days.to_string(),
+            ]);
+            summary.render();
+
+            if let Err(e) = trainer.save(TACTICS_PROGRESS_FILE) {
+                self.interface
+                    .show_error(&format!("Could not save tactics progress: {}", e));
+            }
+
+            if !ConfirmDialog::confirm("Continue training?") {
+                break;
+            }
+        }
     }
 
     fn show_statistics(&self) {
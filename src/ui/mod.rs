@@ -4,7 +4,8 @@
 pub mod integration;
 use crate::engine::{Board, Sq};
 pub use integration::GameController;
-use std::io::{self, Write};
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
 
 // ============================================================================
 // COLOR CODES & STYLING
@@ -839,6 +840,55 @@ impl ProgressBar {
     }
 }
 
+// ============================================================================
+// OUTPUT FORMAT
+// ============================================================================
+
+/// Global rendering mode consulted by [`Table`] and
+/// [`StatsDisplay::show_engine_stats`]: `Pretty` draws the usual box-drawing
+/// tables, `Json` emits machine-readable JSON instead. Stored as a process
+/// global (rather than threaded through every call site) since it reflects a
+/// single front-end's output mode for its whole run, the same way a CLI
+/// picks `--json` once at startup.
+static OUTPUT_FORMAT: AtomicU8 = AtomicU8::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Pretty,
+    Json,
+}
+
+impl OutputFormat {
+    pub fn set(self) {
+        OUTPUT_FORMAT.store(self as u8, Ordering::Relaxed);
+    }
+
+    pub fn current() -> Self {
+        match OUTPUT_FORMAT.load(Ordering::Relaxed) {
+            1 => OutputFormat::Json,
+            _ => OutputFormat::Pretty,
+        }
+    }
+}
+
+/// Escape a string as a JSON string literal, including the surrounding quotes.
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
 // ============================================================================
 // TABLE RENDERER
 // ============================================================================
@@ -861,6 +911,35 @@ impl Table {
     }
 
     pub fn render(&self) {
+        match OutputFormat::current() {
+            OutputFormat::Pretty => self.render_pretty(),
+            OutputFormat::Json => self.render_json(),
+        }
+    }
+
+    /// Each row as a JSON object keyed by header name, e.g.
+    /// `[{"Metric":"Nodes","Value":"123"}]`.
+    fn render_json(&self) {
+        let rows: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let fields: Vec<String> = row
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, cell)| {
+                        self.headers
+                            .get(i)
+                            .map(|header| format!("{}:{}", json_escape(header), json_escape(cell)))
+                    })
+                    .collect();
+                format!("{{{}}}", fields.join(","))
+            })
+            .collect();
+        println!("[{}]", rows.join(","));
+    }
+
+    fn render_pretty(&self) {
         use colors::*;
 
         // Calculate column widths
@@ -931,7 +1010,33 @@ impl Table {
 pub struct StatsDisplay;
 
 impl StatsDisplay {
+    /// Nodes searched per second, given a node count and elapsed milliseconds.
+    /// Shared by `show_engine_stats` and anything else (e.g. perft) that
+    /// wants the same throughput figure.
+    pub fn nodes_per_second(nodes: u64, time_ms: u128) -> u64 {
+        if time_ms > 0 {
+            (nodes as f64 / time_ms as f64 * 1000.0) as u64
+        } else {
+            0
+        }
+    }
+
     pub fn show_engine_stats(nodes: u64, time_ms: u128, tt_hits: u64, tt_probes: u64) {
+        let nps = Self::nodes_per_second(nodes, time_ms);
+        let hit_rate = if tt_probes > 0 {
+            tt_hits as f64 / tt_probes as f64 * 100.0
+        } else {
+            0.0
+        };
+
+        if OutputFormat::current() == OutputFormat::Json {
+            println!(
+                "{{\"nodes\":{},\"time_ms\":{},\"nps\":{},\"tt_probes\":{},\"tt_hits\":{},\"tt_hit_rate\":{:.2}}}",
+                nodes, time_ms, nps, tt_probes, tt_hits, hit_rate
+            );
+            return;
+        }
+
         use colors::*;
 
         println!();
@@ -953,22 +1058,9 @@ impl StatsDisplay {
 
         table.add_row(vec!["Nodes Searched".to_string(), format!("{}", nodes)]);
         table.add_row(vec!["Time Elapsed".to_string(), format!("{}ms", time_ms)]);
-
-        let nps = if time_ms > 0 {
-            (nodes as f64 / time_ms as f64 * 1000.0) as u64
-        } else {
-            0
-        };
         table.add_row(vec!["Nodes/Second".to_string(), format!("{}", nps)]);
-
         table.add_row(vec!["TT Probes".to_string(), format!("{}", tt_probes)]);
         table.add_row(vec!["TT Hits".to_string(), format!("{}", tt_hits)]);
-
-        let hit_rate = if tt_probes > 0 {
-            tt_hits as f64 / tt_probes as f64 * 100.0
-        } else {
-            0.0
-        };
         table.add_row(vec!["TT Hit Rate".to_string(), format!("{:.2}%", hit_rate)]);
 
         table.render();
@@ -976,14 +1068,17 @@ impl StatsDisplay {
     }
 
     pub fn show_position_eval(material: i32, positional: i32, total: i32) {
-        use colors::*;
+        let pretty = OutputFormat::current() == OutputFormat::Pretty;
 
-        println!();
-        println!(
-            "{}{}─── Position Evaluation ───{}",
-            BOLD, BRIGHT_YELLOW, RESET
-        );
-        println!();
+        if pretty {
+            use colors::*;
+            println!();
+            println!(
+                "{}{}─── Position Evaluation ───{}",
+                BOLD, BRIGHT_YELLOW, RESET
+            );
+            println!();
+        }
 
         let mut table = Table::new(vec!["Component".to_string(), "Score".to_string()]);
 
@@ -995,14 +1090,18 @@ impl StatsDisplay {
             "Positional".to_string(),
             format!("{:+.2}", positional as f32 / 100.0),
         ]);
-        table.add_row(vec!["───────────".to_string(), "───────".to_string()]);
+        if pretty {
+            table.add_row(vec!["───────────".to_string(), "───────".to_string()]);
+        }
         table.add_row(vec![
             "Total".to_string(),
             format!("{:+.2}", total as f32 / 100.0),
         ]);
 
         table.render();
-        println!();
+        if pretty {
+            println!();
+        }
     }
 }
 
@@ -1356,6 +1455,141 @@ impl Animation {
         }
         println!("\r                              \r");
     }
+
+    /// Render one frame of a live chess-clock display. Unlike `countdown`,
+    /// this never sleeps — callers (the game loop) redraw it once per
+    /// iteration, so the clock face stays live without blocking input.
+    pub fn clock_tick(white_ms: u64, black_ms: u64, white_to_move: bool) {
+        use colors::*;
+
+        let fmt = |ms: u64| format!("{:02}:{:02}", ms / 60_000, (ms / 1000) % 60);
+        let (white_color, black_color) = if white_to_move {
+            (BRIGHT_GREEN, DIM)
+        } else {
+            (DIM, BRIGHT_GREEN)
+        };
+
+        print!(
+            "\r{}White {}{}   {}Black {}{}   ",
+            white_color,
+            fmt(white_ms),
+            RESET,
+            black_color,
+            fmt(black_ms),
+            RESET
+        );
+        io::stdout().flush().ok();
+    }
+}
+
+// ============================================================================
+// CHESS CLOCK
+// ============================================================================
+
+/// Tracks each side's remaining time under a [`TimeControl`], applying
+/// Fischer increments and the "N moves in T minutes" sudden-death-then-reset
+/// rule. `Unlimited` has no clock, so construction is fallible.
+pub struct Clock {
+    remaining_ms: [u64; 2],
+    increment_ms: u64,
+    moves_per_period: Option<u32>,
+    period_ms: u64,
+    moves_made: [u32; 2],
+    turn_start: Option<std::time::Instant>,
+}
+
+impl Clock {
+    fn side_index(white: bool) -> usize {
+        if white { 0 } else { 1 }
+    }
+
+    /// Build a clock from a [`TimeControl`], or `None` for `Unlimited`.
+    pub fn from_time_control(time_control: TimeControl) -> Option<Self> {
+        let (remaining_ms, increment_ms, moves_per_period, period_ms) = match time_control {
+            TimeControl::Unlimited => return None,
+            TimeControl::FixedTime(ms) => (ms, 0, None, 0),
+            TimeControl::TimeAndIncrement { time, increment } => (time, increment, None, 0),
+            TimeControl::MovesInTime { moves, time } => (time, 0, Some(moves), time),
+        };
+
+        Some(Self {
+            remaining_ms: [remaining_ms, remaining_ms],
+            increment_ms,
+            moves_per_period,
+            period_ms,
+            moves_made: [0, 0],
+            turn_start: None,
+        })
+    }
+
+    /// Start timing the side to move.
+    pub fn start_turn(&mut self) {
+        self.turn_start = Some(std::time::Instant::now());
+    }
+
+    /// Remaining time for `white`, accounting for any turn in progress, so a
+    /// live redraw always shows the clock ticking down.
+    pub fn remaining_ms(&self, white: bool) -> u64 {
+        let idx = Self::side_index(white);
+        let elapsed = self.turn_start.map(|t| t.elapsed().as_millis() as u64).unwrap_or(0);
+        self.remaining_ms[idx].saturating_sub(elapsed)
+    }
+
+    /// Has `white`'s flag fallen (clock at zero)?
+    pub fn flag_fallen(&self, white: bool) -> bool {
+        self.remaining_ms(white) == 0
+    }
+
+    /// Stop timing the side that just moved, charging the elapsed time and
+    /// applying the Fischer increment / period reset. Returns `true` if this
+    /// flagged the side (it ran out of time), in which case no increment or
+    /// reset is applied.
+    pub fn end_turn(&mut self, white: bool) -> bool {
+        let idx = Self::side_index(white);
+        let elapsed = self
+            .turn_start
+            .take()
+            .map(|t| t.elapsed().as_millis() as u64)
+            .unwrap_or(0);
+        self.remaining_ms[idx] = self.remaining_ms[idx].saturating_sub(elapsed);
+
+        if self.remaining_ms[idx] == 0 {
+            return true;
+        }
+
+        self.remaining_ms[idx] += self.increment_ms;
+
+        if let Some(moves_per_period) = self.moves_per_period {
+            self.moves_made[idx] += 1;
+            if self.moves_made[idx] >= moves_per_period {
+                self.moves_made[idx] = 0;
+                self.remaining_ms[idx] += self.period_ms;
+            }
+        }
+
+        false
+    }
+
+    /// Redraw the live clock face for the side currently to move.
+    pub fn render(&self, white_to_move: bool) {
+        Animation::clock_tick(self.remaining_ms(true), self.remaining_ms(false), white_to_move);
+    }
+
+    /// Per-move time budget for `white`: remaining time split over an
+    /// estimate of the moves left (the period length if one is set,
+    /// otherwise a conventional 30-move planning horizon) plus the
+    /// increment, clamped so a move never eats the whole clock.
+    pub fn allocate_move_time(&self, white: bool) -> u64 {
+        let idx = Self::side_index(white);
+        let remaining = self.remaining_ms(white);
+        let moves_left = self
+            .moves_per_period
+            .map(|total| total.saturating_sub(self.moves_made[idx]).max(1) as u64)
+            .unwrap_or(30);
+
+        let budget = remaining / moves_left + self.increment_ms;
+        budget.clamp(remaining.min(100), remaining)
+    }
 }
 
 // ============================================================================
@@ -1380,6 +1614,10 @@ impl MoveHistoryDisplay {
         self.current_move = self.moves.len();
     }
 
+    pub fn moves(&self) -> &[String] {
+        &self.moves
+    }
+
     pub fn display_full(&self) {
         use colors::*;
 
@@ -1484,6 +1722,211 @@ impl ConfirmDialog {
     }
 }
 
+// ============================================================================
+// TAB-COMPLETING PROMPT
+// ============================================================================
+
+/// Default command set offered at the first token of a [`Prompt`] with no
+/// caller-supplied commands.
+pub const PROMPT_COMMANDS: &[&str] = &[
+    "move", "undo", "show", "perf", "fen", "eval", "quit", "help",
+];
+
+#[cfg(unix)]
+struct RawMode {
+    fd: std::os::unix::io::RawFd,
+    original: termios::Termios,
+}
+
+#[cfg(unix)]
+impl RawMode {
+    /// Put stdin into raw mode (no line buffering, no local echo) so Tab
+    /// arrives as a single byte instead of being consumed by the line
+    /// discipline. Fails on non-tty stdin (piped input, test harnesses),
+    /// which the caller treats as "fall back to buffered input".
+    fn enable() -> io::Result<Self> {
+        use std::os::unix::io::AsRawFd;
+        let fd = io::stdin().as_raw_fd();
+        let original = termios::Termios::from_fd(fd)?;
+        let mut raw = original;
+        raw.c_lflag &= !(termios::ICANON | termios::ECHO);
+        termios::tcsetattr(fd, termios::TCSANOW, &raw)?;
+        Ok(Self { fd, original })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = termios::tcsetattr(self.fd, termios::TCSANOW, &self.original);
+    }
+}
+
+#[cfg(not(unix))]
+struct RawMode;
+
+#[cfg(not(unix))]
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "raw mode needs unix"))
+    }
+}
+
+/// Interactive line prompt with Tab-completion, used for the move/command
+/// REPL. Unlike [`ConfirmDialog`]'s single blocking read, this puts the
+/// terminal into raw mode so Tab is handled as a live keypress rather than as
+/// a literal byte that only appears once Enter is pressed. Move completion is
+/// supplied by the caller as a closure returning legal-move strings so this
+/// module stays decoupled from move generation; non-tty stdin falls back to a
+/// plain buffered read with completion disabled.
+pub struct Prompt {
+    commands: Vec<String>,
+}
+
+impl Default for Prompt {
+    fn default() -> Self {
+        Self::new(PROMPT_COMMANDS)
+    }
+}
+
+impl Prompt {
+    pub fn new(commands: &[&str]) -> Self {
+        Self {
+            commands: commands.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// Offset of the token currently being typed, i.e. just past the last space.
+    fn current_token_start(line: &str) -> usize {
+        line.rfind(' ').map(|i| i + 1).unwrap_or(0)
+    }
+
+    /// Completions for the token currently being typed: the command set when
+    /// it's the first token, or `legal_moves` when the first token is `move`.
+    fn completions(&self, line: &str, legal_moves: &[String]) -> Vec<String> {
+        let mut parts = line.splitn(2, ' ');
+        let first = parts.next().unwrap_or("");
+
+        let mut matches: Vec<String> = match parts.next() {
+            None => self
+                .commands
+                .iter()
+                .filter(|c| c.starts_with(first))
+                .cloned()
+                .collect(),
+            Some(rest) if first == "move" => {
+                let prefix = rest.trim_start();
+                legal_moves
+                    .iter()
+                    .filter(|m| m.starts_with(prefix))
+                    .cloned()
+                    .collect()
+            }
+            Some(_) => Vec::new(),
+        };
+
+        matches.sort();
+        matches.dedup();
+        matches
+    }
+
+    /// Read one line from the terminal with Tab-completion. `legal_moves` is
+    /// only invoked when completion is requested with `move` as the first
+    /// token, so the cost of generating moves is paid lazily.
+    pub fn read_line<F>(&self, label: &str, legal_moves: F) -> String
+    where
+        F: Fn() -> Vec<String>,
+    {
+        match RawMode::enable() {
+            Ok(raw) => {
+                let line = self.read_line_raw(label, &legal_moves);
+                drop(raw);
+                line
+            }
+            Err(_) => self.read_line_buffered(label),
+        }
+    }
+
+    fn read_line_raw<F>(&self, label: &str, legal_moves: &F) -> String
+    where
+        F: Fn() -> Vec<String>,
+    {
+        use colors::*;
+
+        let mut line = String::new();
+        print!("{}{} > {}", BRIGHT_CYAN, label, RESET);
+        io::stdout().flush().ok();
+
+        let mut stdin = io::stdin();
+        let mut byte = [0u8; 1];
+        loop {
+            if stdin.read_exact(&mut byte).is_err() {
+                break;
+            }
+
+            match byte[0] {
+                b'\r' | b'\n' => {
+                    println!();
+                    break;
+                }
+                0x7f | 0x08 if line.pop().is_some() => {
+                    print!("\x08 \x08");
+                    io::stdout().flush().ok();
+                }
+                0x7f | 0x08 => {}
+                0x03 => {
+                    line.clear();
+                    println!();
+                    break;
+                }
+                0x09 => {
+                    let moves = if line.starts_with("move ") {
+                        legal_moves()
+                    } else {
+                        Vec::new()
+                    };
+                    let matches = self.completions(&line, &moves);
+                    match matches.as_slice() {
+                        [] => {}
+                        [only] => {
+                            line.truncate(Self::current_token_start(&line));
+                            line.push_str(only);
+                            print!("\r\x1b[K{}{} > {}{}", BRIGHT_CYAN, label, RESET, line);
+                        }
+                        many => {
+                            println!();
+                            println!("{}", many.join("  "));
+                            print!("{}{} > {}{}", BRIGHT_CYAN, label, RESET, line);
+                        }
+                    }
+                    io::stdout().flush().ok();
+                }
+                c if c.is_ascii_graphic() || c == b' ' => {
+                    line.push(c as char);
+                    print!("{}", c as char);
+                    io::stdout().flush().ok();
+                }
+                _ => {}
+            }
+        }
+
+        line.trim().to_string()
+    }
+
+    fn read_line_buffered(&self, label: &str) -> String {
+        use colors::*;
+
+        print!("{}{} > {}", BRIGHT_CYAN, label, RESET);
+        io::stdout().flush().ok();
+
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line).is_err() {
+            return String::new();
+        }
+        line.trim().to_string()
+    }
+}
+
 // ============================================================================
 // EXPORT FUNCTIONS
 // ============================================================================
@@ -1554,4 +1997,128 @@ mod tests {
         assert!(!InputValidator::is_valid_square("z1"));
         assert!(!InputValidator::is_valid_square("e"));
     }
+
+    #[test]
+    fn prompt_completes_commands_at_first_token() {
+        let prompt = Prompt::default();
+        assert_eq!(prompt.completions("m", &[]), vec!["move".to_string()]);
+        assert_eq!(
+            prompt.completions("", &[]),
+            vec!["eval", "fen", "help", "move", "perf", "quit", "show", "undo"]
+        );
+    }
+
+    #[test]
+    fn prompt_completes_legal_moves_after_move_token() {
+        let prompt = Prompt::default();
+        let legal = vec!["e2e4".to_string(), "e2e3".to_string(), "d2d4".to_string()];
+        assert_eq!(
+            prompt.completions("move e2", &legal),
+            vec!["e2e3".to_string(), "e2e4".to_string()]
+        );
+        assert_eq!(prompt.completions("move d2", &legal), vec!["d2d4".to_string()]);
+    }
+
+    #[test]
+    fn prompt_offers_no_move_completions_for_other_commands() {
+        let prompt = Prompt::default();
+        let legal = vec!["e2e4".to_string()];
+        assert!(prompt.completions("undo e", &legal).is_empty());
+    }
+
+    #[test]
+    fn prompt_respects_custom_command_set() {
+        let prompt = Prompt::new(&["go", "stop"]);
+        assert_eq!(prompt.completions("g", &[]), vec!["go".to_string()]);
+        assert!(prompt.completions("m", &[]).is_empty());
+    }
+
+    #[test]
+    fn json_escape_handles_quotes_backslashes_and_control_chars() {
+        assert_eq!(json_escape("a\"b\\c"), "\"a\\\"b\\\\c\"");
+        assert_eq!(json_escape("line1\nline2"), "\"line1\\nline2\"");
+        assert_eq!(json_escape("plain"), "\"plain\"");
+    }
+
+    #[test]
+    fn output_format_defaults_to_pretty_and_round_trips() {
+        // This exercises the process-wide OUTPUT_FORMAT switch, so always
+        // restore it to the default afterwards in case other tests run
+        // concurrently in the same process.
+        assert_eq!(OutputFormat::current(), OutputFormat::Pretty);
+        OutputFormat::Json.set();
+        assert_eq!(OutputFormat::current(), OutputFormat::Json);
+        OutputFormat::Pretty.set();
+        assert_eq!(OutputFormat::current(), OutputFormat::Pretty);
+    }
+
+    #[test]
+    fn clock_unlimited_has_no_time_control() {
+        assert!(Clock::from_time_control(TimeControl::Unlimited).is_none());
+    }
+
+    #[test]
+    fn clock_end_turn_applies_fischer_increment() {
+        let mut clock = Clock::from_time_control(TimeControl::TimeAndIncrement {
+            time: 60_000,
+            increment: 3_000,
+        })
+        .unwrap();
+        clock.start_turn();
+        assert!(!clock.end_turn(true));
+        // Near-zero elapsed time plus the 3s increment.
+        assert!(clock.remaining_ms(true) > 60_000);
+        assert_eq!(clock.remaining_ms(false), 60_000);
+    }
+
+    #[test]
+    fn clock_moves_in_time_resets_after_period() {
+        let mut clock = Clock::from_time_control(TimeControl::MovesInTime {
+            moves: 2,
+            time: 90_000,
+        })
+        .unwrap();
+        clock.start_turn();
+        clock.end_turn(true);
+        assert_eq!(clock.moves_made[0], 1);
+
+        clock.start_turn();
+        clock.end_turn(true);
+        // The second move in the period triggers the sudden-death reset,
+        // crediting another full period on top of whatever remained.
+        assert_eq!(clock.moves_made[0], 0);
+        assert!(clock.remaining_ms(true) > 90_000);
+    }
+
+    #[test]
+    fn clock_flags_a_side_that_runs_out_of_time() {
+        let mut clock = Clock::from_time_control(TimeControl::FixedTime(5_000)).unwrap();
+        clock.remaining_ms = [0, 5_000];
+        assert!(clock.flag_fallen(true));
+        assert!(!clock.flag_fallen(false));
+
+        clock.start_turn();
+        assert!(clock.end_turn(true));
+    }
+
+    #[test]
+    fn clock_allocates_move_time_within_remaining_budget() {
+        let clock = Clock::from_time_control(TimeControl::FixedTime(10_000)).unwrap();
+        let budget = clock.allocate_move_time(true);
+        assert!(budget > 0 && budget < 10_000);
+    }
+
+    #[test]
+    fn clock_never_allocates_more_than_remaining_time() {
+        let clock = Clock {
+            remaining_ms: [40, 40],
+            increment_ms: 0,
+            moves_per_period: None,
+            period_ms: 0,
+            moves_made: [0, 0],
+            turn_start: None,
+        };
+        let budget = clock.allocate_move_time(true);
+        assert!(budget <= 40);
+    }
 }
@@ -16,6 +16,13 @@ use std::fs::File;
 use std::io::{self, BufReader, BufWriter, Read, Write};
 use std::sync::{Arc, Mutex, OnceLock};
 
+/// Version of the key *layout* this build produces, independent of which
+/// constructor generated a given `Zobrist` (runtime-seeded or
+/// `static_tables()`): bump it whenever the meaning or count of any key
+/// table changes, so `load_from_file` can refuse stale saved files instead
+/// of silently producing wrong hashes with them.
+pub const KEY_VERSION: u32 = 1;
+
 // =====================
 // Core Zobrist Structure
 // =====================
@@ -37,6 +44,10 @@ pub struct Zobrist {
     // Full en passant square keys (for maximum precision)
     pub ep_square: [u64; 128],
 
+    // Material signature keys: [piece_type][count 0..=10], XORed together
+    // over all 12 piece types by `material_signature` below.
+    pub material_keys: [[u64; 11]; 12],
+
     // Seed used for generation (for reproducibility)
     seed: u64,
 
@@ -51,6 +62,155 @@ pub struct ZobristStats {
     pub full_rehashes: u64,
     pub collisions_detected: u64,
     pub verifications: u64,
+    /// Times a TT probe found its cluster occupied by a different position
+    /// (i.e. every slot's `key_check` disagreed with the one being looked
+    /// up). This is ordinary bucket contention - expected behavior as the
+    /// table fills up - and is *not* a real 64-bit hash collision, so it's
+    /// tracked separately from `collisions_detected`.
+    pub cluster_contention: u64,
+}
+
+/// Generate the `[piece_type][count]` material-signature keys from `seed`,
+/// independently of the main piece/castling/ep key stream in `with_seed`. Keeping
+/// this on its own derived seed (rather than drawing the next N values off
+/// the same `rng` as everything else) means `load_from_file`, which doesn't
+/// persist this table, can still reconstruct it exactly from `seed` alone
+/// without having to replay the other fields' generation order.
+fn generate_material_keys(seed: u64) -> [[u64; 11]; 12] {
+    let mut rng = StdRng::seed_from_u64(seed ^ 0x4D41_5445_5249_414C);
+    let mut keys = [[0u64; 11]; 12];
+    for row in keys.iter_mut() {
+        for slot in row.iter_mut() {
+            *slot = rng.r#gen();
+        }
+    }
+    keys
+}
+
+// =====================
+// Static (Compile-Time) Key Tables
+// =====================
+//
+// Everything above is generated by a runtime-seeded `StdRng`, which is fine
+// within a single process but means a saved hash is only reproducible if you
+// also persist the exact key file it was generated with. `STATIC_KEY_TABLES`
+// instead bakes a full set of keys into the binary as genuine compile-time
+// constants (computed by a small `const fn` PRNG, since `rand`'s generators
+// aren't `const`-evaluable), so every build produces byte-identical keys
+// with nothing to load or ship separately.
+
+/// A fixed seed for the compile-time tables; unrelated to any runtime
+/// `with_seed` call, and never meant to change unless `KEY_VERSION` also
+/// bumps.
+const STATIC_SEED: u64 = 0x5441_424C_4B45_5953;
+
+/// One step of SplitMix64: returns `(output, next_state)`. `const fn` (no
+/// loops over trait methods, no `rand` dependency) so it can run at compile
+/// time inside `build_static_tables`.
+const fn splitmix64_step(state: u64) -> (u64, u64) {
+    let next_state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = next_state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z, next_state)
+}
+
+/// Every field `with_seed` would otherwise fill from a runtime RNG, computed
+/// once at compile time from `STATIC_SEED`.
+struct StaticKeyTables {
+    pieces: [[u64; 12]; 128],
+    side: u64,
+    castling: [u64; 16],
+    ep_file: [u64; 8],
+    ep_square: [u64; 128],
+    material_keys: [[u64; 11]; 12],
+}
+
+const fn build_static_tables() -> StaticKeyTables {
+    let mut state = STATIC_SEED;
+
+    let mut pieces = [[0u64; 12]; 128];
+    let mut sq = 0usize;
+    while sq < 128 {
+        let mut pc = 0usize;
+        while pc < 12 {
+            let (val, next_state) = splitmix64_step(state);
+            pieces[sq][pc] = val;
+            state = next_state;
+            pc += 1;
+        }
+        sq += 1;
+    }
+
+    let mut castling = [0u64; 16];
+    let mut i = 0usize;
+    while i < 16 {
+        let (val, next_state) = splitmix64_step(state);
+        castling[i] = val;
+        state = next_state;
+        i += 1;
+    }
+
+    let mut ep_file = [0u64; 8];
+    i = 0;
+    while i < 8 {
+        let (val, next_state) = splitmix64_step(state);
+        ep_file[i] = val;
+        state = next_state;
+        i += 1;
+    }
+
+    let mut ep_square = [0u64; 128];
+    i = 0;
+    while i < 128 {
+        let (val, next_state) = splitmix64_step(state);
+        ep_square[i] = val;
+        state = next_state;
+        i += 1;
+    }
+
+    let (side, next_state) = splitmix64_step(state);
+    state = next_state;
+
+    let mut material_keys = [[0u64; 11]; 12];
+    let mut p = 0usize;
+    while p < 12 {
+        let mut c = 0usize;
+        while c < 11 {
+            let (val, next_state) = splitmix64_step(state);
+            material_keys[p][c] = val;
+            state = next_state;
+            c += 1;
+        }
+        p += 1;
+    }
+
+    StaticKeyTables {
+        pieces,
+        side,
+        castling,
+        ep_file,
+        ep_square,
+        material_keys,
+    }
+}
+
+const STATIC_TABLES: StaticKeyTables = build_static_tables();
+
+static STATIC_KEY_TABLES: OnceLock<Zobrist> = OnceLock::new();
+
+fn static_key_tables() -> &'static Zobrist {
+    STATIC_KEY_TABLES.get_or_init(|| Zobrist {
+        pieces: STATIC_TABLES.pieces,
+        side: STATIC_TABLES.side,
+        castling: STATIC_TABLES.castling,
+        ep_file: STATIC_TABLES.ep_file,
+        ep_square: STATIC_TABLES.ep_square,
+        material_keys: STATIC_TABLES.material_keys,
+        seed: STATIC_SEED,
+        stats: ZobristStats::default(),
+    })
 }
 
 // =====================
@@ -107,16 +267,26 @@ impl Zobrist {
             castling,
             ep_file,
             ep_square,
+            material_keys: generate_material_keys(seed),
             seed,
             stats: ZobristStats::default(),
         }
     }
 
-    /// Create Zobrist with Polyglot-compatible keys (for opening book compatibility)
-    pub fn polyglot() -> Self {
-        // Polyglot uses a specific seed and generation pattern
-        // This is a simplified version; full Polyglot compatibility requires exact values
-        Self::with_seed(0x0123456789ABCDEF)
+    /// Create a Zobrist instance from key tables computed at *compile time*
+    /// (see `STATIC_KEY_TABLES` above), rather than generated by a runtime
+    /// RNG. Every build of this crate produces the exact same keys, so
+    /// hashes - and anything keyed by them, like a saved transposition
+    /// table, opening book, or analysis file - stay stable across runs and
+    /// releases without having to ship or load a separate key file.
+    pub fn static_tables() -> Self {
+        static_key_tables().clone()
+    }
+
+    /// The key-layout version this instance's tables were produced against
+    /// (see `KEY_VERSION`).
+    pub fn key_version(&self) -> u32 {
+        KEY_VERSION
     }
 
     // =====================
@@ -274,6 +444,103 @@ impl Zobrist {
         h
     }
 
+    /// Incrementally update hash for a castling move: the king and rook
+    /// both change square in one move, so (unlike `update_move`) this needs
+    /// both pairs of squares rather than a single piece/from/to. Does not
+    /// fold in the castling-*rights* change; call `update_castling` for
+    /// that, as with any other rook or king move.
+    pub fn update_castle(
+        &mut self,
+        current_hash: u64,
+        king_from: Sq,
+        king_to: Sq,
+        rook_from: Sq,
+        rook_to: Sq,
+        side_white: bool,
+    ) -> u64 {
+        self.stats.incremental_updates += 1;
+
+        let mut h = current_hash;
+        let king = if side_white { Piece::WK } else { Piece::BK };
+        let rook = if side_white { Piece::WR } else { Piece::BR };
+
+        if let Some(idx) = Self::piece_index(king) {
+            h ^= self.pieces[king_from][idx];
+            h ^= self.pieces[king_to][idx];
+        }
+        if let Some(idx) = Self::piece_index(rook) {
+            h ^= self.pieces[rook_from][idx];
+            h ^= self.pieces[rook_to][idx];
+        }
+
+        h ^= self.side;
+        h
+    }
+
+    /// Incrementally update hash for a pawn promoting: the pawn leaves
+    /// `from`, and the *promoted* piece (not the pawn) lands on `to`,
+    /// possibly capturing whatever was there.
+    pub fn update_promotion(
+        &mut self,
+        current_hash: u64,
+        from: Sq,
+        to: Sq,
+        pawn: Piece,
+        promoted: Piece,
+        captured: Option<Piece>,
+    ) -> u64 {
+        self.stats.incremental_updates += 1;
+
+        let mut h = current_hash;
+
+        if let Some(idx) = Self::piece_index(pawn) {
+            h ^= self.pieces[from][idx];
+        }
+        if let Some(cap) = captured {
+            if let Some(idx) = Self::piece_index(cap) {
+                h ^= self.pieces[to][idx];
+            }
+        }
+        if let Some(idx) = Self::piece_index(promoted) {
+            h ^= self.pieces[to][idx];
+        }
+
+        h ^= self.side;
+        h
+    }
+
+    /// Incrementally update hash for an en-passant capture: the capturing
+    /// pawn moves `from` -> `to` as normal, but the captured pawn sits on
+    /// `captured_pawn_sq`, not on `to`, so it needs its own square.
+    pub fn update_en_passant(
+        &mut self,
+        current_hash: u64,
+        from: Sq,
+        to: Sq,
+        captured_pawn_sq: Sq,
+        side_white: bool,
+    ) -> u64 {
+        self.stats.incremental_updates += 1;
+
+        let mut h = current_hash;
+        let (pawn, captured_pawn) = if side_white {
+            (Piece::WP, Piece::BP)
+        } else {
+            (Piece::BP, Piece::WP)
+        };
+
+        if let Some(idx) = Self::piece_index(pawn) {
+            h ^= self.pieces[from][idx];
+            h ^= self.pieces[to][idx];
+        }
+        if let Some(idx) = Self::piece_index(captured_pawn) {
+            h ^= self.pieces[captured_pawn_sq][idx];
+        }
+
+        h ^= self.side;
+        h
+    }
+
     /// Update hash for castling rights change
     pub fn update_castling(&self, current_hash: u64, old_rights: u8, new_rights: u8) -> u64 {
         let mut h = current_hash;
@@ -305,11 +572,6 @@ impl Zobrist {
         h
     }
 
-    /// Toggle side to move in hash
-    pub fn toggle_side(&self, current_hash: u64) -> u64 {
-        current_hash ^ self.side
-    }
-
     // =====================
     // Specialized Hashing
     // =====================
@@ -334,29 +596,28 @@ impl Zobrist {
         h
     }
 
-    /// Compute material hash (for endgame tablebase lookups)
-    pub fn material_hash(&self, board: &Board) -> u64 {
-        let mut h = 0u64;
-        let mut piece_counts = [0u8; 12];
-
-        // Count pieces
+    /// Compute a material signature (for endgame/tablebase lookups): a key
+    /// that depends only on how many of each piece type are on the board,
+    /// not where. Unlike `self.pieces`, which is keyed `[square][piece]` and
+    /// has nothing to say about piece *counts*, `material_keys` has one key
+    /// per `(piece type, count)` pair, so XORing in exactly one key per
+    /// piece type (including a count of zero) gives a collision-resistant,
+    /// order-independent signature of the whole material balance.
+    pub fn material_signature(&self, board: &Board) -> u64 {
+        let mut counts = [0usize; 12];
         for sq in 0..128 {
             if (sq & 0x88) != 0 {
                 continue;
             }
             if let Some(idx) = Self::piece_index(board.cells[sq]) {
-                piece_counts[idx] += 1;
+                counts[idx] += 1;
             }
         }
 
-        // Hash based on piece counts (order-independent)
-        for (idx, &count) in piece_counts.iter().enumerate() {
-            for _ in 0..count {
-                // Use a simple hash combining piece type and count
-                h ^= self.pieces[idx][idx].wrapping_mul(count as u64);
-            }
+        let mut h = 0u64;
+        for (idx, &count) in counts.iter().enumerate() {
+            h ^= self.material_keys[idx][count.min(10)];
         }
-
         h
     }
 
@@ -482,6 +743,14 @@ impl Zobrist {
         self.stats = ZobristStats::default();
     }
 
+    /// Record a TT probe whose whole cluster was occupied by a different
+    /// position - normal bucket contention, not a genuine hash collision.
+    /// Kept separate from `collisions_detected`, which is reserved for
+    /// cases `verify_hash` actually proves are wrong.
+    pub fn record_cluster_contention(&mut self) {
+        self.stats.cluster_contention += 1;
+    }
+
     /// Print statistics report
     pub fn print_stats(&self) {
         println!("=== Zobrist Statistics ===");
@@ -490,6 +759,7 @@ impl Zobrist {
         println!("Incremental updates: {}", self.stats.incremental_updates);
         println!("Verifications:       {}", self.stats.verifications);
         println!("Collisions detected: {}", self.stats.collisions_detected);
+        println!("Cluster contention:  {}", self.stats.cluster_contention);
 
         if self.stats.hash_calls > 0 {
             let incremental_pct =
@@ -502,12 +772,16 @@ impl Zobrist {
     // Persistence
     // =====================
 
-    /// Save Zobrist keys to a file (for reproducibility across runs)
+    /// Save Zobrist keys to a file (for reproducibility across runs). The
+    /// file is tagged with `KEY_VERSION` so a later `load_from_file` can
+    /// refuse to load it if the key layout it was written against has since
+    /// changed.
     pub fn save_to_file(&self, path: &str) -> io::Result<()> {
         let file = File::create(path)?;
         let mut writer = BufWriter::new(file);
 
-        // Write seed
+        // Write version, then seed
+        writer.write_all(&KEY_VERSION.to_le_bytes())?;
         writer.write_all(&self.seed.to_le_bytes())?;
 
         // Write piece keys
@@ -532,10 +806,44 @@ impl Zobrist {
         Ok(())
     }
 
-    /// Load Zobrist keys from a file
+    /// Load Zobrist keys from a file, refusing to load one written by a
+    /// build with a different `KEY_VERSION` (the layout may have changed
+    /// underneath it, which would silently produce wrong hashes). Use
+    /// `load_from_file_unchecked` to bypass this check.
     pub fn load_from_file(path: &str) -> io::Result<Self> {
         let file = File::open(path)?;
         let mut reader = BufReader::new(file);
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf)?;
+        let version = u32::from_le_bytes(buf);
+        if version != KEY_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "zobrist key file version {} does not match this build's KEY_VERSION {}; \
+                     use load_from_file_unchecked to load it anyway",
+                    version, KEY_VERSION
+                ),
+            ));
+        }
+        Self::read_tables(&mut reader)
+    }
+
+    /// Like `load_from_file`, but loads the key tables regardless of the
+    /// file's `KEY_VERSION`. Only use this when you know the stored layout
+    /// still matches this build's (e.g. a version bump that didn't actually
+    /// change the table shapes).
+    pub fn load_from_file_unchecked(path: &str) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let mut reader = BufReader::new(file);
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf)?; // discard the version tag
+        Self::read_tables(&mut reader)
+    }
+
+    /// Shared tail of `load_from_file`/`load_from_file_unchecked`: everything
+    /// after the version tag has already been consumed by the caller.
+    fn read_tables(reader: &mut BufReader<File>) -> io::Result<Self> {
         let mut buf = [0u8; 8];
 
         // Read seed
@@ -579,12 +887,176 @@ impl Zobrist {
             castling,
             ep_file,
             ep_square,
+            material_keys: generate_material_keys(seed),
             seed,
             stats: ZobristStats::default(),
         })
     }
 }
 
+/// Piece/letter pairs in the canonical material-string order (king first,
+/// then descending value), one list per color.
+const WHITE_MATERIAL_ORDER: [(Piece, char); 6] = [
+    (Piece::WK, 'K'),
+    (Piece::WQ, 'Q'),
+    (Piece::WR, 'R'),
+    (Piece::WB, 'B'),
+    (Piece::WN, 'N'),
+    (Piece::WP, 'P'),
+];
+const BLACK_MATERIAL_ORDER: [(Piece, char); 6] = [
+    (Piece::BK, 'k'),
+    (Piece::BQ, 'q'),
+    (Piece::BR, 'r'),
+    (Piece::BB, 'b'),
+    (Piece::BN, 'n'),
+    (Piece::BP, 'p'),
+];
+
+/// Render `board`'s material balance as a normalized signature string like
+/// "KQkr": white pieces first (king, queen, rook, bishop, knight, pawn),
+/// then the same for black in lowercase, each letter repeated once per piece
+/// of that type. Two positions with the same material have the same string
+/// regardless of where the pieces actually stand.
+pub fn material_string(board: &Board) -> String {
+    let mut s = String::new();
+    for &(piece, ch) in WHITE_MATERIAL_ORDER.iter().chain(BLACK_MATERIAL_ORDER.iter()) {
+        let count = board.cells.iter().filter(|&&p| p == piece).count();
+        s.extend(std::iter::repeat_n(ch, count));
+    }
+    s
+}
+
+// =====================
+// Polyglot Compatibility
+// =====================
+//
+// `hash_board` above hashes by [square][piece] with per-file en-passant keys,
+// which is close to but not the PolyGlot book format: PolyGlot indexes its
+// key table as `64*kind_of_piece + 8*rank + file` with a specific
+// black/white-interleaved piece order, only XORs the en-passant key when a
+// pawn could actually recapture, and keeps its random table separate from
+// any particular `Zobrist` instance's seed. `polyglot_hash` follows that
+// layout, but its random table (`polyglot_random64`) is generated from a
+// fixed seed, not the published PolyGlot `Random64` values, so its keys do
+// not match the keys stored in published `.bin` opening books.
+// See https://hgm.nubati.net/book_format.html for the reference layout.
+
+const POLYGLOT_RANDOM_COUNT: usize = 781;
+const POLYGLOT_PIECE_OFFSET: usize = 0;
+const POLYGLOT_CASTLE_OFFSET: usize = 768;
+const POLYGLOT_EP_OFFSET: usize = 772;
+const POLYGLOT_TURN_OFFSET: usize = 780;
+
+/// The PolyGlot `Random64` table: entries 0..767 are piece-square keys,
+/// 768..771 are castling-rights keys (white O-O, white O-O-O, black O-O,
+/// black O-O-O), 772..779 are en-passant-file keys (a..h), and 780 is the
+/// side-to-move key.
+///
+/// The 781 values are generated once from a fixed seed; they are not the
+/// published PolyGlot constants. Every offset and XOR in `polyglot_hash`
+/// follows the published layout, but hashes built from this table do not
+/// equal the keys in published `.bin` books.
+fn polyglot_random64() -> &'static [u64; POLYGLOT_RANDOM_COUNT] {
+    static TABLE: OnceLock<[u64; POLYGLOT_RANDOM_COUNT]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut rng = StdRng::seed_from_u64(0x9D2C_5680_1234_5678);
+        let mut table = [0u64; POLYGLOT_RANDOM_COUNT];
+        for slot in table.iter_mut() {
+            *slot = rng.r#gen();
+        }
+        table
+    })
+}
+
+/// Map a piece to PolyGlot's `kind_of_piece` index: black pawn=0, white
+/// pawn=1, black knight=2, white knight=3, ... black king=10, white king=11.
+fn polyglot_piece_kind(piece: Piece) -> Option<usize> {
+    match piece {
+        Piece::BP => Some(0),
+        Piece::WP => Some(1),
+        Piece::BN => Some(2),
+        Piece::WN => Some(3),
+        Piece::BB => Some(4),
+        Piece::WB => Some(5),
+        Piece::BR => Some(6),
+        Piece::WR => Some(7),
+        Piece::BQ => Some(8),
+        Piece::WQ => Some(9),
+        Piece::BK => Some(10),
+        Piece::WK => Some(11),
+        Piece::Empty => None,
+    }
+}
+
+/// The en-passant file to hash, if any: PolyGlot only XORs the en-passant
+/// key when a pawn of the side to move is actually standing next to the
+/// target square, not merely whenever `board.ep` happens to be set.
+fn polyglot_ep_file(board: &Board) -> Option<usize> {
+    let ep_sq = board.ep?;
+    let file = (ep_sq & 7) as i32;
+    let rank = (ep_sq >> 4) as i32;
+    let capturer = if board.side_white { Piece::WP } else { Piece::BP };
+    let capture_rank = if board.side_white { rank - 1 } else { rank + 1 };
+    if !(0..8).contains(&capture_rank) {
+        return None;
+    }
+    for df in [-1, 1] {
+        let f = file + df;
+        if !(0..8).contains(&f) {
+            continue;
+        }
+        let candidate = ((capture_rank as usize) << 4) | (f as usize);
+        if board.cells[candidate] == capturer {
+            return Some(file as usize);
+        }
+    }
+    None
+}
+
+/// Hash `board` with PolyGlot's key layout, for probing `.bin` opening books
+/// (see `PolyglotBook` in the `polyglot` module). The random table is
+/// `polyglot_random64`'s seeded one, so only books keyed with that same
+/// table give matches.
+pub fn polyglot_hash(board: &Board) -> u64 {
+    let table = polyglot_random64();
+    let mut h = 0u64;
+
+    for s in 0..128 {
+        if (s & 0x88) != 0 {
+            continue;
+        }
+        if let Some(kind) = polyglot_piece_kind(board.cells[s]) {
+            let rank = s >> 4;
+            let file = s & 7;
+            h ^= table[POLYGLOT_PIECE_OFFSET + 64 * kind + 8 * rank + file];
+        }
+    }
+
+    if board.castling & 1 != 0 {
+        h ^= table[POLYGLOT_CASTLE_OFFSET];
+    }
+    if board.castling & 2 != 0 {
+        h ^= table[POLYGLOT_CASTLE_OFFSET + 1];
+    }
+    if board.castling & 4 != 0 {
+        h ^= table[POLYGLOT_CASTLE_OFFSET + 2];
+    }
+    if board.castling & 8 != 0 {
+        h ^= table[POLYGLOT_CASTLE_OFFSET + 3];
+    }
+
+    if let Some(file) = polyglot_ep_file(board) {
+        h ^= table[POLYGLOT_EP_OFFSET + file];
+    }
+
+    if board.side_white {
+        h ^= table[POLYGLOT_TURN_OFFSET];
+    }
+
+    h
+}
+
 // =====================
 // Global Zobrist Instance (Singleton Pattern)
 // =====================
@@ -663,6 +1135,89 @@ mod tests {
         std::fs::remove_file(path).ok();
     }
 
+    #[test]
+    fn test_load_from_file_rejects_version_mismatch() {
+        let zob1 = Zobrist::with_seed(8888);
+        let path = "/tmp/zobrist_test_version_mismatch.bin";
+        zob1.save_to_file(path).expect("Save failed");
+
+        // Corrupt the version tag (first 4 bytes) so it no longer matches
+        // KEY_VERSION.
+        let mut bytes = std::fs::read(path).unwrap();
+        let bad_version = KEY_VERSION.wrapping_add(1).to_le_bytes();
+        bytes[0..4].copy_from_slice(&bad_version);
+        std::fs::write(path, &bytes).unwrap();
+
+        assert!(Zobrist::load_from_file(path).is_err());
+        let zob2 = Zobrist::load_from_file_unchecked(path).expect("Unchecked load failed");
+        assert_eq!(zob1.seed, zob2.seed);
+        assert_eq!(zob1.side, zob2.side);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_static_tables_deterministic_and_matches_key_version() {
+        let a = Zobrist::static_tables();
+        let b = Zobrist::static_tables();
+
+        assert_eq!(a.side, b.side);
+        assert_eq!(a.pieces, b.pieces);
+        assert_eq!(a.key_version(), KEY_VERSION);
+    }
+
+    #[test]
+    fn test_update_castle_matches_full_rehash() {
+        let mut zob = Zobrist::with_seed(42);
+        let before = Board::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+        let after = Board::from_fen("4k3/8/8/8/8/8/8/5RK1 b - - 0 1");
+
+        let before_hash = zob.hash_board_quiet(&before);
+        let mut h = zob.update_castle(before_hash, 0x04, 0x06, 0x07, 0x05, true);
+        h = zob.update_castling(h, before.castling, after.castling);
+
+        assert_eq!(h, zob.hash_board_quiet(&after));
+    }
+
+    #[test]
+    fn test_update_promotion_matches_full_rehash() {
+        let mut zob = Zobrist::with_seed(42);
+
+        // No capture.
+        let before = Board::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+        let after = Board::from_fen("Q3k3/8/8/8/8/8/8/4K3 b - - 0 1");
+        let before_hash = zob.hash_board_quiet(&before);
+        let h = zob.update_promotion(before_hash, 0x60, 0x70, Piece::WP, Piece::WQ, None);
+        assert_eq!(h, zob.hash_board_quiet(&after));
+
+        // Promoting with a capture on the destination square.
+        let before = Board::from_fen("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+        let after = Board::from_fen("1Q2k3/8/8/8/8/8/8/4K3 b - - 0 1");
+        let before_hash = zob.hash_board_quiet(&before);
+        let h = zob.update_promotion(
+            before_hash,
+            0x60,
+            0x71,
+            Piece::WP,
+            Piece::WQ,
+            Some(Piece::BR),
+        );
+        assert_eq!(h, zob.hash_board_quiet(&after));
+    }
+
+    #[test]
+    fn test_update_en_passant_matches_full_rehash() {
+        let mut zob = Zobrist::with_seed(42);
+        let before = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1");
+        let after = Board::from_fen("4k3/8/3P4/8/8/8/8/4K3 b - - 0 1");
+
+        let before_hash = zob.hash_board_quiet(&before);
+        let mut h = zob.update_en_passant(before_hash, 0x44, 0x53, 0x43, true);
+        h = zob.update_ep(h, Some(0x53), None);
+
+        assert_eq!(h, zob.hash_board_quiet(&after));
+    }
+
     #[test]
     fn test_zobrist_keys_nonzero() {
         let zob = Zobrist::new();
@@ -671,4 +1226,65 @@ mod tests {
         assert_ne!(zob.castling[0], 0, "Castling keys should be non-zero");
         assert_ne!(zob.pieces[0][0], 0, "Piece keys should be non-zero");
     }
+
+    #[test]
+    fn test_material_signature_is_order_independent() {
+        let zob = Zobrist::new();
+        // Same material (K+Q vs K+R), pieces on different squares.
+        let a = Board::from_fen("4k3/8/8/3q4/8/8/8/4K2R w - - 0 1");
+        let b = Board::from_fen("7k/8/8/8/2q5/8/8/R3K3 w - - 0 1");
+        assert_eq!(zob.material_signature(&a), zob.material_signature(&b));
+
+        // Different material should (almost certainly) hash differently.
+        let c = Board::from_fen("4k3/8/8/3q4/8/8/8/4K2Q w - - 0 1");
+        assert_ne!(zob.material_signature(&a), zob.material_signature(&c));
+    }
+
+    #[test]
+    fn test_material_string_formats_like_kqkr() {
+        let board = Board::from_fen("r3k3/8/8/8/8/8/8/4KQ2 w - - 0 1");
+        assert_eq!(material_string(&board), "KQkr");
+    }
+
+    #[test]
+    fn test_polyglot_hash_deterministic_and_sensitive_to_side() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let h1 = polyglot_hash(&board);
+        let h2 = polyglot_hash(&board);
+        assert_eq!(h1, h2, "same position should hash the same");
+
+        let mut black_to_move = board.clone();
+        black_to_move.side_white = false;
+        assert_ne!(
+            h1,
+            polyglot_hash(&black_to_move),
+            "side to move must affect the hash"
+        );
+    }
+
+    #[test]
+    fn test_polyglot_ep_only_hashed_when_capturable() {
+        // White just played e2-e4; no black pawn is adjacent to recapture en
+        // passant, so `ep` being set shouldn't affect the hash at all.
+        let no_capturer =
+            Board::from_fen("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 2");
+        let mut without_ep = no_capturer.clone();
+        without_ep.ep = None;
+        assert_eq!(
+            polyglot_hash(&no_capturer),
+            polyglot_hash(&without_ep),
+            "ep key shouldn't be hashed when no pawn can capture"
+        );
+
+        // Black pawn on d4 can actually capture e3 en passant.
+        let capturer =
+            Board::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 3");
+        let mut without_ep = capturer.clone();
+        without_ep.ep = None;
+        assert_ne!(
+            polyglot_hash(&capturer),
+            polyglot_hash(&without_ep),
+            "ep key should be hashed when a pawn can actually capture"
+        );
+    }
 }